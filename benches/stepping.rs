@@ -0,0 +1,136 @@
+//! Steps-per-second of the interpreted and compiled deterministic engines
+//!
+//! Run with `cargo bench --bench stepping`
+
+use std::time::{ Duration, Instant };
+
+use tm_engine::deterministic::catalogue::busy_beaver;
+use tm_engine::deterministic::machine::TuringMachine;
+use tm_engine::tape::{ Tape, TwoWayTape };
+use tm_engine::util::{ ComputationStatus, State, Symbol, Transition };
+
+const STEPS: usize = 20_000_000;
+
+/// Sweeps between the start marker and the end of the tape forever, appending a `1` on every pass
+fn sweeping_machine() -> TuringMachine {
+    let states = vec![
+        State::new_state("start".to_string()),
+        State::new_state("accept".to_string()),
+        State::new_state("reject".to_string()),
+        State::new_state("right".to_string()),
+        State::new_state("left".to_string()),
+    ];
+
+    let language_symbols = vec![ Symbol::new_symbol('1') ];
+    let tape_symbols = vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ];
+
+    let transitions = vec![
+        vec![
+            Transition::new_transition(2, 0, 1),
+            Transition::new_transition(3, 1, 1),
+            Transition::new_transition(2, 2, 1),
+        ],
+        vec![], // accept transitions
+        vec![], // reject transitions
+        vec![
+            Transition::new_transition(4, 2, -1),
+            Transition::new_transition(2, 1, 1),
+            Transition::new_transition(3, 2, 1),
+        ],
+        vec![
+            Transition::new_transition(2, 0, 1),
+            Transition::new_transition(3, 1, 1),
+            Transition::new_transition(4, 2, -1),
+        ]
+    ];
+
+    TuringMachine::new(states, language_symbols, tape_symbols, transitions, 0, 1, 2)
+}
+
+/// Like [`sweeping_machine`] but spreads each sweep over `width` states to stress the transition table
+fn wide_sweeping_machine(width: usize) -> TuringMachine {
+    let mut states = vec![
+        State::new_state("start".to_string()),
+        State::new_state("accept".to_string()),
+        State::new_state("reject".to_string()),
+    ];
+    let mut transitions = vec![
+        vec![
+            Transition::new_transition(2, 0, 1),
+            Transition::new_transition(3, 1, 1),
+            Transition::new_transition(2, 2, 1),
+        ],
+        vec![], // accept transitions
+        vec![], // reject transitions
+    ];
+
+    let right = |k: usize| 3 + k % width;
+    let left = |k: usize| 3 + width + k % width;
+
+    for k in 0..width {
+        states.push(State::new_state(format!("right_{k}")));
+        transitions.push(vec![
+            Transition::new_transition(left(k), 2, -1),
+            Transition::new_transition(2, 1, 1),
+            Transition::new_transition(right(k + 1), 2, 1),
+        ]);
+    }
+
+    for k in 0..width {
+        states.push(State::new_state(format!("left_{k}")));
+        transitions.push(vec![
+            Transition::new_transition(2, 0, 1),
+            Transition::new_transition(right(k), 1, 1),
+            Transition::new_transition(left(k + 1), 2, -1),
+        ]);
+    }
+
+    let language_symbols = vec![ Symbol::new_symbol('1') ];
+    let tape_symbols = vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ];
+
+    TuringMachine::new(states, language_symbols, tape_symbols, transitions, 0, 1, 2)
+}
+
+fn report(name: &str, steps: usize, elapsed: Duration) -> f64 {
+    let rate = steps as f64 / elapsed.as_secs_f64();
+    println!("{:<12} {:>12} steps in {:>8.3}s  {:>14.0} steps/s", name, steps, elapsed.as_secs_f64(), rate);
+    rate
+}
+
+fn compare(name: &str, machine: &TuringMachine) {
+    println!("{name}");
+    let compiled = machine.compile().expect("benchmark machine should compile");
+
+    let mut interpreted = machine.bounded_compute("1", (Some(STEPS), None)).unwrap();
+    let begin = Instant::now();
+    interpreted.run();
+    let interpreted_rate = report("interpreted", interpreted.view_clock().time, begin.elapsed());
+
+    let mut fast = compiled.bounded_compute("1", (Some(STEPS), None)).unwrap();
+    let begin = Instant::now();
+    fast.run();
+    let compiled_rate = report("compiled", fast.view_clock().time, begin.elapsed());
+
+    assert_eq!(interpreted.view_status(), &ComputationStatus::Timeout);
+    assert_eq!(fast.view_status(), interpreted.view_status());
+    assert_eq!(fast.view_tape(), interpreted.view_tape());
+
+    println!("speedup      {:.2}x\n", compiled_rate / interpreted_rate);
+}
+
+fn main() {
+    compare("sweep, 5 states", &sweeping_machine());
+    compare("sweep, 20003 states", &wide_sweeping_machine(10_000));
+
+    println!("busy beaver, 5 states");
+    let machine = busy_beaver(5).unwrap();
+    let compiled = machine.compile().unwrap();
+    let mut fast = compiled.compute_on_tape::<TwoWayTape>("", (None, None)).unwrap();
+    let begin = Instant::now();
+    fast.run();
+    report("compiled", fast.view_clock().time, begin.elapsed());
+
+    assert_eq!(fast.view_status(), &ComputationStatus::Accept);
+    assert_eq!(fast.view_clock().time, 47_176_870);
+    assert_eq!(fast.view_tape().to_vec().iter().filter(|id| **id == 2).count(), 4098);
+}
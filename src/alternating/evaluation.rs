@@ -0,0 +1,181 @@
+//! Bounded evaluation of the computation tree of an alternating machine
+//!
+//! The tree is searched depth first with an explicit stack, so deep branches cannot overflow the
+//! call stack. A branch that returns to a configuration on the current path is undecided, and a
+//! value that relied on such a cut depends on the path it was reached by. Only values that did not
+//! rely on a cut above their own configuration are remembered.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::errors::TmError;
+use crate::util::{ ComputationStatus, Transition };
+use super::machine::{ AlternatingMachine, StateKind };
+
+/// The most tape cells the configurations on one branch may hold together
+const MAX_BRANCH_CELLS: usize = 1 << 22;
+
+/// The outcome of evaluating a machine on one word
+///
+/// The status is [`ComputationStatus::Accept`] or [`ComputationStatus::Reject`] when the bounded
+/// tree settles acceptance, and otherwise the limit that left it undecided. A branch that returns
+/// to a configuration it has already passed through is undecided as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Evaluation {
+    pub status: ComputationStatus,
+    /// The number of distinct configurations whose value was remembered
+    pub configurations: usize
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Configuration {
+    state: usize,
+    head: usize,
+    cells: Vec<usize>
+}
+
+/// A configuration being evaluated, with the value of the choices taken from it so far
+struct Frame {
+    configuration: Rc<Configuration>,
+    steps: usize,
+    next_choice: usize,
+    status: ComputationStatus,
+    decisive: ComputationStatus,
+    otherwise: ComputationStatus,
+    /// The shallowest depth on the path that a cut below this frame returned to
+    lowest_cut: usize
+}
+
+struct Evaluator<'a> {
+    machine: &'a AlternatingMachine,
+    max_space: Option<usize>,
+    /// The value of each configuration with the number of steps it was evaluated with
+    memo: HashMap<Rc<Configuration>, (ComputationStatus, usize)>,
+    /// The depth of each configuration on the branch being evaluated
+    path: HashMap<Rc<Configuration>, usize>,
+    stack: Vec<Frame>,
+    cells: usize
+}
+
+impl Evaluation {
+    pub(crate) fn evaluate(machine: &AlternatingMachine, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<Self, TmError> {
+        let initial = Configuration { state: machine.view_start_state(), head: 0, cells: machine.encode(word)? };
+        let mut evaluator = Evaluator { machine, max_space: limits.1, memo: HashMap::new(), path: HashMap::new(), stack: vec![], cells: 0 };
+        let steps = limits.0.map_or(usize::MAX, |limit| limit.saturating_sub(1));
+
+        let status = evaluator.value(initial, steps)?;
+        Ok(Evaluation { status, configurations: evaluator.memo.len() })
+    }
+}
+
+impl Evaluator<'_> {
+    /// The value of a configuration when at most `steps` more transitions may be taken
+    fn value(&mut self, configuration: Configuration, steps: usize) -> Result<ComputationStatus, TmError> {
+        let mut result = self.enter(configuration, steps)?;
+
+        loop {
+            if let Some((status, cut)) = result {
+                let Some(frame) = self.stack.last_mut() else {
+                    return Ok(status);
+                };
+
+                frame.lowest_cut = frame.lowest_cut.min(cut);
+                if status == frame.decisive {
+                    frame.status = status;
+                    frame.next_choice = usize::MAX;
+                } else if status != frame.otherwise && frame.status == frame.otherwise {
+                    frame.status = status;
+                }
+            }
+
+            let frame = self.stack.last_mut().unwrap();
+            let machine = self.machine;
+            let configuration = &frame.configuration;
+            let choices = &machine.view_transitions()[configuration.state][configuration.cells[configuration.head]];
+
+            result = match choices.get(frame.next_choice) {
+                Some(transition) => {
+                    frame.next_choice += 1;
+                    let steps = frame.steps - 1;
+                    match self.successor(transition) {
+                        Some(next) => self.enter(next, steps)?,
+                        None => Some((ComputationStatus::Spaceout, usize::MAX))
+                    }
+                },
+                None => Some(self.leave())
+            };
+        }
+    }
+
+    /// Start evaluating a configuration, or give its value straight away when it is already known,
+    /// along with the depth of the path it was cut at
+    fn enter(&mut self, configuration: Configuration, steps: usize) -> Result<Option<(ComputationStatus, usize)>, TmError> {
+        let (decisive, otherwise) = match self.machine.view_kinds()[configuration.state] {
+            StateKind::Accept => return Ok(Some((ComputationStatus::Accept, usize::MAX))),
+            StateKind::Reject => return Ok(Some((ComputationStatus::Reject, usize::MAX))),
+            StateKind::Existential => (ComputationStatus::Accept, ComputationStatus::Reject),
+            StateKind::Universal => (ComputationStatus::Reject, ComputationStatus::Accept)
+        };
+
+        // Settled values hold for any number of steps, undecided ones only for as many steps or fewer
+        if let Some((status, evaluated_with)) = self.memo.get(&configuration) {
+            if matches!(status, ComputationStatus::Accept | ComputationStatus::Reject) || *evaluated_with >= steps {
+                return Ok(Some((*status, usize::MAX)));
+            }
+        }
+
+        if let Some(depth) = self.path.get(&configuration) {
+            return Ok(Some((ComputationStatus::Timeout, *depth)));
+        }
+
+        if steps == 0 {
+            return Ok(Some((ComputationStatus::Timeout, usize::MAX)));
+        }
+
+        self.cells += configuration.cells.len();
+        if self.cells > MAX_BRANCH_CELLS {
+            return Err(TmError::new(format!(
+                "A branch {} steps deep holds more than {} tape cells, too many to evaluate.", self.stack.len(), MAX_BRANCH_CELLS
+            )));
+        }
+
+        let configuration = Rc::new(configuration);
+        self.path.insert(Rc::clone(&configuration), self.stack.len());
+        self.stack.push(Frame { configuration, steps, next_choice: 0, status: otherwise, decisive, otherwise, lowest_cut: usize::MAX });
+        Ok(None)
+    }
+
+    /// Finish the configuration on top of the stack, remembering its value unless it relied on a cut
+    /// above it
+    fn leave(&mut self) -> (ComputationStatus, usize) {
+        let frame = self.stack.pop().unwrap();
+        let depth = self.stack.len();
+        self.path.remove(&frame.configuration);
+        self.cells -= frame.configuration.cells.len();
+
+        if frame.lowest_cut >= depth {
+            self.memo.insert(frame.configuration, (frame.status, frame.steps));
+            (frame.status, usize::MAX)
+        } else {
+            (frame.status, frame.lowest_cut)
+        }
+    }
+
+    /// The configuration after taking a transition from the top of the stack, or `None` when it
+    /// reaches the space limit
+    fn successor(&self, transition: &Transition) -> Option<Configuration> {
+        let configuration = &self.stack.last().unwrap().configuration;
+        let mut cells = configuration.cells.clone();
+        cells[configuration.head] = transition.write_symbol_id();
+
+        let head = (configuration.head as i64 + transition.direction() as i64).max(0) as usize;
+        if head >= cells.len() {
+            cells.resize(head + 1, 0);
+            if self.max_space.is_some_and(|limit| cells.len() >= limit) {
+                return None;
+            }
+        }
+
+        Some(Configuration { state: transition.next_state_id(), head, cells })
+    }
+}
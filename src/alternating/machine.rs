@@ -0,0 +1,75 @@
+use crate::deterministic::computation::encode_symbols;
+use crate::deterministic::machine::check_definition;
+use crate::errors::TmError;
+use crate::util::{ State, Symbol, Transition };
+use super::evaluation::Evaluation;
+
+/// How the value of a configuration is derived from the configurations after it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateKind {
+    /// Accepts when some transition leads to acceptance, and rejects without transitions
+    Existential,
+    /// Accepts when every transition leads to acceptance, and accepts without transitions
+    Universal,
+    Accept,
+    Reject
+}
+
+pub struct AlternatingMachine {
+    states: Vec<State>,
+    kinds: Vec<StateKind>,
+    tape_symbols: Vec<Symbol>,
+    language_symbols: Vec<Symbol>,
+    transitions: Vec<Vec<Vec<Transition>>>,
+    start_state: usize
+}
+
+impl AlternatingMachine {
+    /// Create a machine where `kinds[q]` tags state `q` and `transitions[q][s]` holds the choices
+    /// from state `q` on symbol `s`
+    pub fn new(states: Vec<State>, kinds: Vec<StateKind>, language_symbols: Vec<Symbol>, tape_symbols: Vec<Symbol>, transitions: Vec<Vec<Vec<Transition>>>, start_state: usize) -> Result<Self, TmError> {
+        check_definition(states.len(), &tape_symbols, &language_symbols, [start_state; 3])?;
+
+        if kinds.len() != states.len() {
+            return Err(TmError::new(format!("There are {} state kinds for {} states.", kinds.len(), states.len())));
+        }
+
+        let symbols = tape_symbols.len() + language_symbols.len();
+        for (state, name) in states.iter().enumerate() {
+            if matches!(kinds[state], StateKind::Accept | StateKind::Reject) {
+                continue;
+            }
+
+            let row = transitions.get(state).map_or(&[][..], |row| &row[..]);
+            if row.len() != symbols {
+                return Err(TmError::new(format!("State {} has {} sets of transitions for {} symbols.", name, row.len(), symbols)));
+            }
+
+            if row.iter().flatten().any(|t| t.next_state_id() >= states.len() || t.write_symbol_id() >= symbols) {
+                return Err(TmError::new(format!("State {} has a transition to a missing state or symbol.", name)));
+            }
+        }
+
+        Ok(Self { states, kinds, tape_symbols, language_symbols, transitions, start_state })
+    }
+
+    /// Decide whether the machine accepts a word, following branches for fewer than `limits.0`
+    /// steps while their tape stays below `limits.1` cells
+    ///
+    /// Fails when a single branch grows too large to keep in memory, which bounding the space avoids.
+    pub fn evaluate(&self, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<Evaluation, TmError> {
+        Evaluation::evaluate(self, word, limits)
+    }
+
+    /// The initial tape contents for a word
+    pub(crate) fn encode(&self, word: &str) -> Result<Vec<usize>, TmError> {
+        encode_symbols(&self.tape_symbols, &self.language_symbols, word)
+    }
+
+    pub fn view_states(&self) -> &Vec<State> { &self.states }
+    pub fn view_kinds(&self) -> &Vec<StateKind> { &self.kinds }
+    pub fn view_tape_symbols(&self) -> &Vec<Symbol> { &self.tape_symbols }
+    pub fn view_language_symbols(&self) -> &Vec<Symbol> { &self.language_symbols }
+    pub fn view_transitions(&self) -> &Vec<Vec<Vec<Transition>>> { &self.transitions }
+    pub fn view_start_state(&self) -> usize { self.start_state }
+}
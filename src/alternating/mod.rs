@@ -0,0 +1,16 @@
+//! # Alternating Turing Machines
+//!
+//! Machines where every state is tagged existential, universal, accepting or rejecting. A
+//! configuration in an existential state accepts when some transition from it leads to acceptance,
+//! and one in a universal state accepts when every transition does. Acceptance is decided by
+//! evaluating the computation tree up to a depth and space bound, remembering the value of every
+//! configuration whose value does not depend on the branch it was reached by.
+//!
+//! * [`machine`] - The machine definition with its state tags
+//! * [`evaluation`] - The bounded, memoized evaluation of the computation tree
+
+pub mod machine;
+pub mod evaluation;
+
+pub use crate::alternating::machine::{ AlternatingMachine, StateKind };
+pub use crate::alternating::evaluation::Evaluation;
@@ -0,0 +1,228 @@
+//! # tm
+//!
+//! Run and inspect machines from the shell. Machines are read from machine files (`.tm`, see
+//! [`format`](mod@format)), programs (`.prog`, see [`program`]) or universal encodings (`.utm`, see
+//! [`universal`]).
+//!
+//! Exit codes are `0` when a run accepts or a command succeeds, `1` when a run rejects or a checked
+//! machine is invalid, `2` for usage errors and unreadable machines, and `3` and `4` when a run
+//! reaches its time or space limit.
+
+use std::collections::HashMap;
+use std::process::ExitCode;
+
+use tm_engine::deterministic::{ analysis, codegen, format, latex, program, universal };
+use tm_engine::deterministic::display::DisplayOptions;
+use tm_engine::deterministic::dot::{ self, DotOptions };
+use tm_engine::deterministic::machine::TuringMachine;
+use tm_engine::errors::TmError;
+use tm_engine::tape::Tape;
+use tm_engine::util::ComputationStatus;
+
+const USAGE: &str = "\
+Usage:
+  tm run <machine> <word> [--time N] [--space N]
+  tm trace <machine> <word> [--time N] [--space N] [--window N] [--color]
+  tm check <machine>
+  tm convert <machine> <tm|utm|dot|tikz|table|rust|c> [--word W]
+  tm dot <machine> [--hide-reject] [--trace W] [--time N] [--space N]
+
+Machines are machine files (.tm), programs (.prog) or universal encodings (.utm).";
+
+const VALUE_FLAGS: [&str; 5] = ["--time", "--space", "--window", "--word", "--trace"];
+const SWITCHES: [&str; 2] = ["--color", "--hide-reject"];
+
+/// The positional arguments and flags of a command
+struct Arguments {
+    positional: Vec<String>,
+    flags: HashMap<String, String>
+}
+
+impl Arguments {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut arguments = Arguments { positional: vec![], flags: HashMap::new() };
+        let mut args = args.peekable();
+
+        while let Some(arg) = args.next() {
+            if VALUE_FLAGS.contains(&arg.as_str()) {
+                let value = args.next().ok_or_else(|| format!("{} needs a value.", arg))?;
+                arguments.flags.insert(arg, value);
+            } else if SWITCHES.contains(&arg.as_str()) {
+                arguments.flags.insert(arg, String::new());
+            } else if arg.starts_with("--") {
+                return Err(format!("Unknown flag {}.", arg));
+            } else {
+                arguments.positional.push(arg);
+            }
+        }
+
+        Ok(arguments)
+    }
+
+    fn number(&self, flag: &str) -> Result<Option<usize>, String> {
+        self.flags.get(flag)
+            .map(|value| value.parse().map_err(|_| format!("{} needs a number, not {}.", flag, value)))
+            .transpose()
+    }
+
+    fn limits(&self) -> Result<(Option<usize>, Option<usize>), String> {
+        Ok((self.number("--time")?, self.number("--space")?))
+    }
+
+    fn switch(&self, flag: &str) -> bool {
+        self.flags.contains_key(flag)
+    }
+}
+
+/// Read and validate a machine, choosing the format by the file extension
+fn load(path: &str) -> Result<TuringMachine, String> {
+    parse(path, &read(path)?)
+}
+
+fn read(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))
+}
+
+fn parse(path: &str, text: &str) -> Result<TuringMachine, String> {
+    let machine = match path.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("prog") => program::compile(text).map(TuringMachine::from),
+        Some("utm") => universal::decode(text.trim()).map(|(machine, _)| machine),
+        _ => format::from_text(text)
+    };
+
+    machine.and_then(|machine| machine.validate().map(|_| machine)).map_err(|e: TmError| format!("{}: {}", path, e.message))
+}
+
+fn status_code(status: &ComputationStatus) -> ExitCode {
+    ExitCode::from(match status {
+        ComputationStatus::Accept => 0,
+        ComputationStatus::Reject => 1,
+        ComputationStatus::Timeout => 3,
+        ComputationStatus::Spaceout => 4,
+        ComputationStatus::Executing => 2
+    })
+}
+
+fn run(arguments: &Arguments, trace: bool) -> Result<ExitCode, String> {
+    let [path, word] = arguments.positional.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let machine = load(path)?;
+    let mut computation = machine.bounded_compute(word, arguments.limits()?).map_err(|e| e.message)?;
+
+    if trace {
+        let options = DisplayOptions { window: arguments.number("--window")?, color: arguments.switch("--color") };
+        println!("{}", computation.display(options));
+        while computation.view_status() == &ComputationStatus::Executing {
+            computation.step();
+            if computation.view_status() != &ComputationStatus::Timeout {
+                println!("{}", computation.display(options));
+            }
+        }
+    } else {
+        computation.run();
+    }
+
+    let symbols = machine.symbol_characters();
+    let tape: String = computation.view_tape().to_vec().iter().map(|id| symbols[*id]).collect();
+    println!("status {:?}", computation.view_status());
+    println!("time {}", computation.view_clock().time);
+    println!("space {}", computation.view_clock().space);
+    println!("head {}", computation.view_head_position());
+    println!("tape {}", tape.trim_end_matches(symbols[0]));
+
+    Ok(status_code(computation.view_status()))
+}
+
+fn check(arguments: &Arguments) -> Result<ExitCode, String> {
+    let [path] = arguments.positional.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let machine = match parse(path, &read(path)?) {
+        Ok(machine) => machine,
+        Err(message) => {
+            println!("invalid {}", message);
+            return Ok(ExitCode::from(1));
+        }
+    };
+
+    let name = |state: &usize| machine.view_states()[*state].to_string();
+    let symbols = machine.symbol_characters();
+    let report = analysis::analyze(&machine);
+
+    println!("valid {} states, {} symbols", machine.view_states().len(), symbols.len());
+    for (label, states) in [("unreachable", &report.unreachable_states), ("stuck", &report.stuck_states)] {
+        if !states.is_empty() {
+            println!("{} {}", label, states.iter().map(name).collect::<Vec<String>>().join(" "));
+        }
+    }
+    if !report.unused_symbols.is_empty() {
+        println!("unused {}", report.unused_symbols.iter().map(|symbol| symbols[*symbol].to_string()).collect::<Vec<String>>().join(" "));
+    }
+    if !report.dead_transitions.is_empty() {
+        println!("dead transitions {}", report.dead_transitions.len());
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn convert(arguments: &Arguments) -> Result<ExitCode, String> {
+    let [path, target] = arguments.positional.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let machine = load(path)?;
+    let word = arguments.flags.get("--word").map_or("", |word| word.as_str());
+    let output = match target.as_str() {
+        "tm" => format::to_text(&machine),
+        "utm" => universal::encode(&machine, word).map(|encoding| encoding + "\n"),
+        "dot" => Ok(machine.to_dot()),
+        "tikz" => Ok(latex::to_tikz(&machine, false)),
+        "table" => Ok(latex::to_table(&machine)),
+        "rust" => codegen::to_rust(&machine),
+        "c" => codegen::to_c(&machine),
+        _ => return Err(format!("Unknown format {}.\n\n{}", target, USAGE))
+    };
+
+    print!("{}", output.map_err(|e| e.message)?);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn diagram(arguments: &Arguments) -> Result<ExitCode, String> {
+    let [path] = arguments.positional.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let machine = load(path)?;
+    let highlight = match arguments.flags.get("--trace") {
+        Some(word) => dot::trace(&machine, word, arguments.limits()?).map_err(|e| e.message)?,
+        None => vec![]
+    };
+
+    print!("{}", machine.to_dot_with(&DotOptions { hide_reject: arguments.switch("--hide-reject"), highlight }));
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let command = args.next().unwrap_or_default();
+
+    let result = Arguments::parse(args).and_then(|arguments| match command.as_str() {
+        "run" => run(&arguments, false),
+        "trace" => run(&arguments, true),
+        "check" => check(&arguments),
+        "convert" => convert(&arguments),
+        "dot" => diagram(&arguments),
+        _ => Err(USAGE.to_string())
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::from(2)
+        }
+    }
+}
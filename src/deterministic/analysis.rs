@@ -0,0 +1,109 @@
+//! # Static Analysis
+//!
+//! Find the parts of a machine that can never matter to a computation, such as the dead states left
+//! behind when machines are generated or composed.
+//!
+//! Reachability tracks which symbols can ever be on the tape alongside which states can be entered:
+//! a transition can only be taken from a reachable state on a symbol that is either part of the
+//! initial tape or written by another transition that can be taken.
+
+use std::collections::VecDeque;
+
+use super::machine::TuringMachine;
+
+/// The findings of [`analyze`], listing states and symbols by id in increasing order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    /// States that some computation enters
+    pub reachable_states: Vec<usize>,
+    /// States that no computation enters
+    pub unreachable_states: Vec<usize>,
+    /// `(state, symbol)` pairs of transitions no computation takes
+    pub dead_transitions: Vec<(usize, usize)>,
+    /// Tape symbols, other than the blank and start marker, that no computation writes
+    pub unused_symbols: Vec<usize>,
+    /// States from which neither the accept nor the reject state can be entered
+    pub stuck_states: Vec<usize>
+}
+
+impl Report {
+    /// Whether the machine has nothing to prune
+    pub fn is_clean(&self) -> bool {
+        self.unreachable_states.is_empty() && self.dead_transitions.is_empty() && self.unused_symbols.is_empty() && self.stuck_states.is_empty()
+    }
+}
+
+/// Analyze the transition graph of a machine
+pub fn analyze(machine: &TuringMachine) -> Report {
+    let states = machine.view_states().len();
+    let tape_symbols = machine.view_tape_symbols().len();
+    let symbols = tape_symbols + machine.view_language_symbols().len();
+    let halting = |state: usize| state == machine.view_accept_state() || state == machine.view_reject_state();
+    let row = |state: usize| if halting(state) {
+        &[][..]
+    } else {
+        machine.view_transitions().get(state).map_or(&[][..], |row| &row[..])
+    };
+
+    // Grow the reachable states and present symbols together until neither changes
+    let mut reachable = vec![false; states];
+    let mut present: Vec<bool> = (0..symbols).map(|symbol| symbol < 2 || symbol >= tape_symbols).collect();
+    let mut written = vec![false; symbols];
+    reachable[machine.view_start_state()] = true;
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for state in 0..states {
+            if !reachable[state] {
+                continue;
+            }
+
+            for (symbol, transition) in row(state).iter().enumerate() {
+                if present[symbol] {
+                    let (next, write) = (transition.next_state_id(), transition.write_symbol_id());
+                    changed |= !reachable[next] || !present[write];
+                    reachable[next] = true;
+                    present[write] = true;
+                    written[write] = true;
+                }
+            }
+        }
+    }
+
+    // Search backwards from the halting states over every transition
+    let mut predecessors = vec![vec![]; states];
+    for state in 0..states {
+        for transition in row(state) {
+            predecessors[transition.next_state_id()].push(state);
+        }
+    }
+
+    let mut halts = vec![false; states];
+    let mut queue: VecDeque<usize> = (0..states).filter(|state| halting(*state)).collect();
+    while let Some(state) = queue.pop_front() {
+        if !halts[state] {
+            halts[state] = true;
+            queue.extend(predecessors[state].iter().copied());
+        }
+    }
+
+    let mut report = Report::default();
+    for state in 0..states {
+        if reachable[state] {
+            report.reachable_states.push(state);
+        } else {
+            report.unreachable_states.push(state);
+        }
+
+        if !halts[state] {
+            report.stuck_states.push(state);
+        }
+
+        let dead = (0..row(state).len()).filter(|symbol| !reachable[state] || !present[*symbol]);
+        report.dead_transitions.extend(dead.map(|symbol| (state, symbol)));
+    }
+    report.unused_symbols = (2..tape_symbols).filter(|symbol| !written[*symbol]).collect();
+
+    report
+}
@@ -0,0 +1,99 @@
+//! Assemble machines from named states and character based rules
+
+use std::collections::HashMap;
+
+use crate::util::{ State, Symbol, Transition };
+use super::machine::TuringMachine;
+
+/// Builds a machine whose states are created on first use, rejecting on every symbol without a rule
+///
+/// The start, accept and reject states are always the states `0`, `1` and `2`. Symbols without a
+/// rule are left in place and the head stays put, unless [`Builder::missing_direction`] says otherwise.
+pub(crate) struct Builder {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    rows: Vec<HashMap<char, (usize, char, i32)>>,
+    tape_symbols: Vec<char>,
+    language_symbols: Vec<char>,
+    missing_direction: i32
+}
+
+impl Builder {
+    pub(crate) fn new(tape_symbols: &[char], language_symbols: &[char]) -> Self {
+        let mut builder = Builder {
+            names: vec![],
+            ids: HashMap::new(),
+            rows: vec![],
+            tape_symbols: tape_symbols.to_vec(),
+            language_symbols: language_symbols.to_vec(),
+            missing_direction: 0
+        };
+        builder.state("start");
+        builder.state("accept");
+        builder.state("reject");
+        builder
+    }
+
+    /// Move the head by `direction` on every symbol without a rule
+    pub(crate) fn missing_direction(&mut self, direction: i32) {
+        self.missing_direction = direction;
+    }
+
+    /// Every symbol of the machine except those in `excluded`
+    pub(crate) fn symbols_except(&self, excluded: &str) -> String {
+        self.tape_symbols.iter()
+            .chain(self.language_symbols.iter())
+            .filter(|c| !excluded.contains(**c))
+            .collect()
+    }
+
+    pub(crate) fn state(&mut self, name: &str) -> usize {
+        match self.ids.get(name) {
+            Some(val) => *val,
+            None => {
+                self.names.push(name.to_string());
+                self.ids.insert(name.to_string(), self.names.len() - 1);
+                self.rows.push(HashMap::new());
+                self.names.len() - 1
+            }
+        }
+    }
+
+    /// In state `from` on each of `reads`, write `write` (or leave the symbol), move and enter `to`
+    pub(crate) fn rule(&mut self, from: &str, reads: &str, write: Option<char>, direction: i32, to: &str) {
+        let from = self.state(from);
+        let to = self.state(to);
+
+        for read in reads.chars() {
+            let previous = self.rows[from].insert(read, (to, write.unwrap_or(read), direction));
+            assert!(previous.is_none(), "Duplicate rule for state {} on {}", self.names[from], read);
+        }
+    }
+
+    pub(crate) fn build(self) -> TuringMachine {
+        let characters: Vec<char> = self.tape_symbols.iter().chain(self.language_symbols.iter()).copied().collect();
+        let reject = 2;
+
+        let transitions = self.rows.iter().enumerate().map(|(i, row)| {
+            if i == 1 || i == reject {
+                return vec![];
+            }
+
+            characters.iter().enumerate().map(|(read_id, read)| match row.get(read) {
+                Some((next, write, direction)) => {
+                    let write_id = characters.iter().position(|c| c == write).unwrap();
+                    Transition::new_transition(*next, write_id, *direction)
+                },
+                None => Transition::new_transition(reject, read_id, self.missing_direction)
+            }).collect()
+        }).collect();
+
+        TuringMachine::new(
+            self.names.into_iter().map(State::new_state).collect(),
+            self.language_symbols.into_iter().map(Symbol::new_symbol).collect(),
+            self.tape_symbols.into_iter().map(Symbol::new_symbol).collect(),
+            transitions,
+            0, 1, reject
+        )
+    }
+}
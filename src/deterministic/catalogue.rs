@@ -0,0 +1,220 @@
+//! # Catalogue of Example Machines
+//!
+//! Ready-made machines from Sipser and other standard references, useful as examples and as
+//! regression benchmarks.
+//!
+//! Every machine uses `_` as the blank and `>` as the start marker, and begins by stepping off the
+//! start marker onto the first symbol of the input.
+
+use crate::errors::TmError;
+use super::builder::Builder;
+use super::machine::TuringMachine;
+
+/// Decides $\{ 0^{2^n} \mid n \geq 0 \}$ by repeatedly crossing off every other `0` (Sipser's $M_2$)
+pub fn powers_of_two() -> TuringMachine {
+    let mut b = Builder::new(&['_', '>', 'x'], &['0']);
+
+    b.rule("start", ">", None, 1, "q1");
+    b.rule("q1", "0", Some('_'), 1, "q2");
+    b.rule("q2", "x", None, 1, "q2");
+    b.rule("q2", "_", None, 1, "accept");
+    b.rule("q2", "0", Some('x'), 1, "q3");
+    b.rule("q3", "x", None, 1, "q3");
+    b.rule("q3", "0", None, 1, "q4");
+    b.rule("q3", "_", None, -1, "q5");
+    b.rule("q4", "x", None, 1, "q4");
+    b.rule("q4", "0", Some('x'), 1, "q3");
+    b.rule("q5", "0x", None, -1, "q5");
+    b.rule("q5", "_", None, 1, "q2");
+
+    b.build()
+}
+
+/// Decides $\{ w\#w \mid w \in \{0,1\}^* \}$ by zig-zagging across the `#` (Sipser's $M_1$)
+pub fn w_hash_w() -> TuringMachine {
+    let mut b = Builder::new(&['_', '>', 'x'], &['0', '1', '#']);
+
+    b.rule("start", ">", None, 1, "q1");
+    b.rule("q1", "0", Some('x'), 1, "q2");
+    b.rule("q1", "1", Some('x'), 1, "q3");
+    b.rule("q1", "#", None, 1, "q8");
+    b.rule("q2", "01", None, 1, "q2");
+    b.rule("q2", "#", None, 1, "q4");
+    b.rule("q3", "01", None, 1, "q3");
+    b.rule("q3", "#", None, 1, "q5");
+    b.rule("q4", "x", None, 1, "q4");
+    b.rule("q4", "0", Some('x'), -1, "q6");
+    b.rule("q5", "x", None, 1, "q5");
+    b.rule("q5", "1", Some('x'), -1, "q6");
+    b.rule("q6", "01x", None, -1, "q6");
+    b.rule("q6", "#", None, -1, "q7");
+    b.rule("q7", "01", None, -1, "q7");
+    b.rule("q7", "x", None, 1, "q1");
+    b.rule("q8", "x", None, 1, "q8");
+    b.rule("q8", "_", None, 1, "accept");
+
+    b.build()
+}
+
+/// Decides $\{ a^n b^n c^n \mid n \geq 0 \}$ by crossing off one of each letter per pass
+pub fn a_n_b_n_c_n() -> TuringMachine {
+    let mut b = Builder::new(&['_', '>', 'x', 'y', 'z'], &['a', 'b', 'c']);
+
+    b.rule("start", ">", None, 1, "cross_a");
+    b.rule("cross_a", "a", Some('x'), 1, "cross_b");
+    b.rule("cross_a", "y", None, 1, "check_y");
+    b.rule("cross_a", "_", None, 1, "accept");
+    b.rule("cross_b", "ay", None, 1, "cross_b");
+    b.rule("cross_b", "b", Some('y'), 1, "cross_c");
+    b.rule("cross_c", "bz", None, 1, "cross_c");
+    b.rule("cross_c", "c", Some('z'), -1, "back");
+    b.rule("back", "abyz", None, -1, "back");
+    b.rule("back", "x", None, 1, "cross_a");
+    b.rule("check_y", "y", None, 1, "check_y");
+    b.rule("check_y", "z", None, 1, "check_z");
+    b.rule("check_z", "z", None, 1, "check_z");
+    b.rule("check_z", "_", None, 1, "accept");
+
+    b.build()
+}
+
+/// Decides the palindromes over $\{0,1\}$ by crossing off matching symbols at both ends
+pub fn palindromes() -> TuringMachine {
+    let mut b = Builder::new(&['_', '>', 'x'], &['0', '1']);
+
+    b.rule("start", ">", None, 1, "first");
+    b.rule("first", "x_", None, 1, "accept");
+    b.rule("back", "01", None, -1, "back");
+    b.rule("back", "x", None, 1, "first");
+
+    for (bit, other) in [("0", "1"), ("1", "0")] {
+        let carry = format!("carry_{bit}");
+        let last = format!("last_{bit}");
+
+        b.rule("first", bit, Some('x'), 1, &carry);
+        b.rule(&carry, "01", None, 1, &carry);
+        b.rule(&carry, "x_", None, -1, &last);
+        b.rule(&last, bit, Some('x'), -1, "back");
+        b.rule(&last, "x", None, 1, "accept");
+        b.rule(&last, other, None, 1, "reject");
+    }
+
+    b.build()
+}
+
+/// Adds two binary numbers written as `a+b`, accepting with `a + b` left on the tape
+///
+/// Repeatedly decrements `b` and increments `a` until `b` is zero, shifting `+b` to the right
+/// whenever `a` needs another digit.
+pub fn binary_addition() -> TuringMachine {
+    let mut b = Builder::new(&['_', '>'], &['0', '1', '+']);
+
+    b.rule("start", ">", None, 1, "to_end");
+    b.rule("to_end", "01+", None, 1, "to_end");
+    b.rule("to_end", "_", None, -1, "borrow");
+
+    b.rule("borrow", "0", Some('1'), -1, "borrow");
+    b.rule("borrow", "1", Some('0'), -1, "to_plus");
+    b.rule("borrow", "+", Some('_'), 1, "erase");
+    b.rule("erase", "01", Some('_'), 1, "erase");
+    b.rule("erase", "_", None, -1, "return");
+    b.rule("return", "01_", None, -1, "return");
+    b.rule("return", ">", None, 0, "accept");
+
+    b.rule("to_plus", "01", None, -1, "to_plus");
+    b.rule("to_plus", "+", None, -1, "carry");
+    b.rule("carry", "1", Some('0'), -1, "carry");
+    b.rule("carry", "0", Some('1'), 1, "to_end");
+    b.rule("carry", ">", None, 1, "overflow");
+
+    // Every digit of a is now zero, so a leading one and a zero inserted before the + carry it
+    b.rule("overflow", "0", Some('1'), 1, "find_plus");
+    b.rule("overflow", "+", Some('1'), 1, "shift_+");
+    b.rule("find_plus", "0", None, 1, "find_plus");
+    b.rule("find_plus", "+", Some('0'), 1, "shift_+");
+    for carried in ['0', '1', '+'] {
+        let shift = format!("shift_{carried}");
+        b.rule(&shift, "0", Some(carried), 1, "shift_0");
+        b.rule(&shift, "1", Some(carried), 1, "shift_1");
+        b.rule(&shift, "_", Some(carried), 0, "borrow");
+    }
+
+    b.build()
+}
+
+/// Multiplies unary numbers written as `1^m*1^n`, accepting with `1^m*1^n=1^{mn}` on the tape
+///
+/// Copies `1^n` to the end of the tape once for every `1` of `1^m`.
+pub fn unary_multiplication() -> TuringMachine {
+    let mut b = Builder::new(&['_', '>', '=', 'x', 'y'], &['1', '*']);
+
+    b.rule("start", ">", None, 1, "setup");
+    b.rule("setup", "1*", None, 1, "setup");
+    b.rule("setup", "_", Some('='), -1, "rewind");
+    b.rule("rewind", "1*=", None, -1, "rewind");
+    b.rule("rewind", ">", None, 1, "pick_a");
+
+    b.rule("pick_a", "x", None, 1, "pick_a");
+    b.rule("pick_a", "1", Some('x'), 1, "to_b");
+    b.rule("pick_a", "*", None, -1, "unmark_a");
+    b.rule("to_b", "1", None, 1, "to_b");
+    b.rule("to_b", "*", None, 1, "pick_b");
+
+    b.rule("pick_b", "y", None, 1, "pick_b");
+    b.rule("pick_b", "1", Some('y'), 1, "append");
+    b.rule("pick_b", "=", None, -1, "unmark_b");
+    b.rule("append", "1=", None, 1, "append");
+    b.rule("append", "_", Some('1'), -1, "back_b");
+    b.rule("back_b", "1=", None, -1, "back_b");
+    b.rule("back_b", "y", None, 1, "pick_b");
+
+    b.rule("unmark_b", "y", Some('1'), -1, "unmark_b");
+    b.rule("unmark_b", "*", None, -1, "back_a");
+    b.rule("back_a", "1", None, -1, "back_a");
+    b.rule("back_a", "x", None, 1, "pick_a");
+    b.rule("unmark_a", "x", Some('1'), -1, "unmark_a");
+    b.rule("unmark_a", ">", None, 0, "accept");
+
+    b.build()
+}
+
+/// The busy beaver champion with `states` states and two symbols, for 2 to 5 states
+///
+/// Run on the empty word, halting accepts after 6, 21, 107 and 47,176,870 steps, leaving 4, 5, 13
+/// and 4098 `1`s on the tape. The three state machine is the champion for the number of steps.
+///
+/// # Tape
+///
+/// The champions are defined on a two-way infinite blank tape, so these machines only behave as
+/// described when started with
+/// [`compute_on_tape::<TwoWayTape>`](crate::deterministic::machine::TuringMachine::compute_on_tape)
+/// (see [`TwoWayTape`](crate::tape::TwoWayTape)). They read the start marker as a blank and move
+/// left past it, which every other tape stops at the left end.
+pub fn busy_beaver(states: usize) -> Result<TuringMachine, TmError> {
+    // Each state reads a blank or a 1 and writes, moves and enters the next state, with H halting
+    let table: &[(&str, &str, &str)] = match states {
+        2 => &[("A", "1RB", "1LB"), ("B", "1LA", "1RH")],
+        3 => &[("A", "1RB", "1RH"), ("B", "1LB", "0RC"), ("C", "1LC", "1LA")],
+        4 => &[("A", "1RB", "1LB"), ("B", "1LA", "0LC"), ("C", "1RH", "1LD"), ("D", "1RD", "0RA")],
+        5 => &[("A", "1RB", "1LC"), ("B", "1RC", "1RB"), ("C", "1RD", "0LE"), ("D", "1LA", "1LD"), ("E", "1RH", "0LA")],
+        _ => return Err(TmError::new(format!("No busy beaver champion is known for {} states.", states)))
+    };
+
+    let mut b = Builder::new(&['_', '>'], &['1']);
+    let name = |state: char| match state {
+        'A' => "start".to_string(),
+        'H' => "accept".to_string(),
+        other => other.to_string()
+    };
+
+    for (state, on_blank, on_one) in table {
+        for (read, action) in [("_>", on_blank), ("1", on_one)] {
+            let action: Vec<char> = action.chars().collect();
+            let write = if action[0] == '1' { '1' } else { '_' };
+            let direction = if action[1] == 'R' { 1 } else { -1 };
+            b.rule(&name(state.chars().next().unwrap()), read, Some(write), direction, &name(action[2]));
+        }
+    }
+
+    Ok(b.build())
+}
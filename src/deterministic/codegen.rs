@@ -0,0 +1,266 @@
+//! # Code Generation
+//!
+//! Emit a standalone program specialised to a single [`TuringMachine`].
+//!
+//! The generated program takes the word as its first argument and optional time and space limits
+//! as its second and third, with the same meaning as the limits of a bounded
+//! [`Computation`](super::computation::Computation). When the machine halts it prints
+//!
+//! ```text
+//! status Accept
+//! time 7
+//! space 6
+//! head 5
+//! tape 1 3 3 3 2 0
+//! ```
+//!
+//! matching what `Computation::run` reports for the same word. Invalid words exit with code `2`
+//! and missing transitions with code `3`.
+
+use std::fmt::{ self, Write };
+
+use crate::errors::TmError;
+use crate::util::Transition;
+use super::machine::TuringMachine;
+
+/// Generate a Rust program that runs the machine
+///
+/// Every state becomes an arm of a `match` on the current state holding a `match` on the read
+/// symbol, writing directly into a `Vec` tape.
+pub fn to_rust(machine: &TuringMachine) -> Result<String, TmError> {
+    check_machine(machine)?;
+
+    let mut source = String::new();
+    write_rust(&mut source, machine).map_err(unwritten)?;
+    Ok(source)
+}
+
+fn write_rust(source: &mut String, machine: &TuringMachine) -> fmt::Result {
+    writeln!(source, "// Generated by tm-engine from a Turing Machine with {} states", machine.view_states().len())?;
+    writeln!(source, "use std::process::exit;\n")?;
+    writeln!(source, "fn main() {{")?;
+    writeln!(source, "    let args: Vec<String> = std::env::args().collect();")?;
+    writeln!(source, "    let word = args.get(1).cloned().unwrap_or_default();")?;
+    writeln!(source, "    let max_time: usize = args.get(2).map_or(usize::MAX, |arg| arg.parse().expect(\"invalid time limit\"));")?;
+    writeln!(source, "    let max_space: usize = args.get(3).map_or(usize::MAX, |arg| arg.parse().expect(\"invalid space limit\"));\n")?;
+
+    writeln!(source, "    let mut tape: Vec<u32> = vec![1];")?;
+    writeln!(source, "    for (i, w) in word.chars().enumerate() {{")?;
+    writeln!(source, "        tape.push(match w {{")?;
+    for (symbol, id) in symbol_ids(machine) {
+        writeln!(source, "            {:?} => {},", symbol, id)?;
+    }
+    writeln!(source, "            _ => {{ eprintln!(\"Invalid symbol {{}} found at position {{}}.\", w, i); exit(2) }}")?;
+    writeln!(source, "        }});")?;
+    writeln!(source, "    }}\n")?;
+
+    writeln!(source, "    let mut head: usize = 0;")?;
+    writeln!(source, "    let mut time: usize = 0;")?;
+    writeln!(source, "    let mut space: usize = tape.len();")?;
+    writeln!(source, "    let mut state: usize = {};\n", machine.view_start_state())?;
+
+    writeln!(source, "    let status = loop {{")?;
+    writeln!(source, "        time += 1;")?;
+    writeln!(source, "        if time >= max_time {{ break \"Timeout\"; }}\n")?;
+    writeln!(source, "        match state {{")?;
+    for (i, row) in machine.view_transitions().iter().enumerate() {
+        if row.is_empty() {
+            continue;
+        }
+
+        writeln!(source, "            // {:?}", machine.view_states()[i].as_str())?;
+        writeln!(source, "            {} => match tape[head] {{", i)?;
+        for (symbol, transition) in row.iter().enumerate() {
+            writeln!(source, "                {} => {{ tape[head] = {}; state = {}; {} }}",
+                symbol, transition.write_symbol_id(), transition.next_state_id(), rust_move(transition))?;
+        }
+        writeln!(source, "                symbol => {{ eprintln!(\"No transition from state {{}} on symbol {{}}\", state, symbol); exit(3) }}")?;
+        writeln!(source, "            }},")?;
+    }
+    writeln!(source, "            _ => {{ eprintln!(\"No transition from state {{}} on symbol {{}}\", state, tape[head]); exit(3) }}")?;
+    writeln!(source, "        }}\n")?;
+
+    writeln!(source, "        if head >= tape.len() {{")?;
+    writeln!(source, "            tape.resize(head + 1, 0);")?;
+    writeln!(source, "            space = tape.len();")?;
+    writeln!(source, "            if space >= max_space {{ break \"Spaceout\"; }}")?;
+    writeln!(source, "        }}\n")?;
+    writeln!(source, "        if state == {} {{ break \"Accept\"; }}", machine.view_accept_state())?;
+    writeln!(source, "        if state == {} {{ break \"Reject\"; }}", machine.view_reject_state())?;
+    writeln!(source, "    }};\n")?;
+
+    writeln!(source, "    let cells: Vec<String> = tape.iter().map(|cell| cell.to_string()).collect();")?;
+    writeln!(source, "    println!(\"status {{}}\", status);")?;
+    writeln!(source, "    println!(\"time {{}}\", time);")?;
+    writeln!(source, "    println!(\"space {{}}\", space);")?;
+    writeln!(source, "    println!(\"head {{}}\", head);")?;
+    writeln!(source, "    println!(\"tape {{}}\", cells.join(\" \"));")?;
+    writeln!(source, "}}")
+}
+
+/// Generate a C99 program that runs the machine
+///
+/// Every state becomes a label followed by a `switch` on the read symbol, jumping straight to the
+/// label of the next state. Only ASCII symbols are supported.
+pub fn to_c(machine: &TuringMachine) -> Result<String, TmError> {
+    check_machine(machine)?;
+
+    if let Some(symbol) = machine.view_tape_symbols().iter()
+        .chain(machine.view_language_symbols().iter())
+        .find(|symbol| !symbol.as_char().is_ascii()) {
+        return Err(TmError::new(format!("Symbol {} cannot be used in generated C.", symbol)));
+    }
+
+    let mut source = String::new();
+    write_c(&mut source, machine).map_err(unwritten)?;
+    Ok(source)
+}
+
+fn write_c(source: &mut String, machine: &TuringMachine) -> fmt::Result {
+    writeln!(source, "// Generated by tm-engine from a Turing Machine with {} states", machine.view_states().len())?;
+    writeln!(source, "#include <stdint.h>")?;
+    writeln!(source, "#include <stdio.h>")?;
+    writeln!(source, "#include <stdlib.h>")?;
+    writeln!(source, "#include <string.h>\n")?;
+
+    writeln!(source, "static uint32_t *tape;")?;
+    writeln!(source, "static size_t length, capacity, head, space, max_space;\n")?;
+    writeln!(source, "static int grow(void) {{")?;
+    writeln!(source, "    if (head < length) return 0;")?;
+    writeln!(source, "    if (head >= capacity) {{")?;
+    writeln!(source, "        while (head >= capacity) capacity *= 2;")?;
+    writeln!(source, "        tape = realloc(tape, capacity * sizeof *tape);")?;
+    writeln!(source, "        if (!tape) {{ fprintf(stderr, \"Out of memory\\n\"); exit(1); }}")?;
+    writeln!(source, "    }}")?;
+    writeln!(source, "    memset(tape + length, 0, (head + 1 - length) * sizeof *tape);")?;
+    writeln!(source, "    length = head + 1;")?;
+    writeln!(source, "    space = length;")?;
+    writeln!(source, "    return space >= max_space;")?;
+    writeln!(source, "}}\n")?;
+
+    writeln!(source, "int main(int argc, char **argv) {{")?;
+    writeln!(source, "    const char *word = argc > 1 ? argv[1] : \"\";")?;
+    writeln!(source, "    size_t max_time = argc > 2 ? (size_t) strtoull(argv[2], NULL, 10) : SIZE_MAX;")?;
+    writeln!(source, "    max_space = argc > 3 ? (size_t) strtoull(argv[3], NULL, 10) : SIZE_MAX;")?;
+    writeln!(source, "    size_t time = 0, state = 0, i;")?;
+    writeln!(source, "    const char *status;\n")?;
+    writeln!(source, "    capacity = strlen(word) + 16;")?;
+    writeln!(source, "    tape = malloc(capacity * sizeof *tape);")?;
+    writeln!(source, "    if (!tape) {{ fprintf(stderr, \"Out of memory\\n\"); return 1; }}")?;
+    writeln!(source, "    tape[0] = 1;")?;
+    writeln!(source, "    length = 1;")?;
+    writeln!(source, "    for (i = 0; word[i]; i++) {{")?;
+    writeln!(source, "        switch (word[i]) {{")?;
+    for (symbol, id) in symbol_ids(machine) {
+        writeln!(source, "            case {}: tape[length++] = {}; break;", c_char(symbol), id)?;
+    }
+    writeln!(source, "            default: fprintf(stderr, \"Invalid symbol %c found at position %zu.\\n\", word[i], i); return 2;")?;
+    writeln!(source, "        }}")?;
+    writeln!(source, "    }}")?;
+    writeln!(source, "    head = 0;")?;
+    writeln!(source, "    space = length;")?;
+    writeln!(source, "    goto state_{};\n", machine.view_start_state())?;
+
+    let accept = machine.view_accept_state();
+    let reject = machine.view_reject_state();
+    for i in 0..machine.view_states().len() {
+        let row = machine.view_transitions().get(i).map_or(&[][..], |row| &row[..]);
+
+        writeln!(source, "    // {:?}", machine.view_states()[i].as_str())?;
+        writeln!(source, "state_{}:", i)?;
+        writeln!(source, "    state = {};", i)?;
+        writeln!(source, "    if (++time >= max_time) {{ status = \"Timeout\"; goto halt; }}")?;
+        writeln!(source, "    switch (tape[head]) {{")?;
+        for (symbol, transition) in row.iter().enumerate() {
+            let next = transition.next_state_id();
+            let jump = if next == accept {
+                format!("state = {}; status = \"Accept\"; goto halt;", next)
+            } else if next == reject {
+                format!("state = {}; status = \"Reject\"; goto halt;", next)
+            } else {
+                format!("goto state_{};", next)
+            };
+
+            writeln!(source, "        case {}: tape[head] = {}; {} if (grow()) {{ state = {}; status = \"Spaceout\"; goto halt; }} {}",
+                symbol, transition.write_symbol_id(), c_move(transition), next, jump)?;
+        }
+        writeln!(source, "        default: fprintf(stderr, \"No transition from state %zu on symbol %u\\n\", state, (unsigned) tape[head]); return 3;")?;
+        writeln!(source, "    }}\n")?;
+    }
+
+    writeln!(source, "halt:")?;
+    writeln!(source, "    printf(\"status %s\\n\", status);")?;
+    writeln!(source, "    printf(\"time %zu\\n\", time);")?;
+    writeln!(source, "    printf(\"space %zu\\n\", space);")?;
+    writeln!(source, "    printf(\"head %zu\\n\", head);")?;
+    writeln!(source, "    printf(\"tape\");")?;
+    writeln!(source, "    for (i = 0; i < length; i++) printf(\" %u\", (unsigned) tape[i]);")?;
+    writeln!(source, "    printf(\"\\n\");")?;
+    writeln!(source, "    free(tape);")?;
+    writeln!(source, "    return 0;")?;
+    writeln!(source, "}}")
+}
+
+fn unwritten(_: fmt::Error) -> TmError {
+    TmError::new("Could not write the generated source.".to_string())
+}
+
+/// Check that every transition refers to an existing state and symbol
+fn check_machine(machine: &TuringMachine) -> Result<(), TmError> {
+    let states = machine.view_states().len();
+    let symbols = machine.view_tape_symbols().len() + machine.view_language_symbols().len();
+
+    for (i, row) in machine.view_transitions().iter().enumerate() {
+        for (j, transition) in row.iter().enumerate() {
+            if i >= states || j >= symbols || transition.next_state_id() >= states || transition.write_symbol_id() >= symbols {
+                return Err(TmError::new(
+                    format!("Transition {} from state {} on symbol {} is out of range.", transition, i, j)
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The symbol id each character of a word is loaded as, language symbols taking precedence
+fn symbol_ids(machine: &TuringMachine) -> Vec<(char, usize)> {
+    let offset = machine.view_tape_symbols().len();
+    let mut ids: Vec<(char, usize)> = vec![];
+
+    let language = machine.view_language_symbols().iter().enumerate().map(|(i, symbol)| (symbol.as_char(), i + offset));
+    let tape = machine.view_tape_symbols().iter().enumerate().map(|(i, symbol)| (symbol.as_char(), i));
+
+    for (symbol, id) in language.chain(tape) {
+        if !ids.iter().any(|(seen, _)| *seen == symbol) {
+            ids.push((symbol, id));
+        }
+    }
+
+    ids
+}
+
+fn rust_move(transition: &Transition) -> String {
+    match transition.direction() {
+        0 => String::new(),
+        d if d > 0 => format!("head += {};", d),
+        d => format!("head = head.saturating_sub({});", d.unsigned_abs())
+    }
+}
+
+fn c_move(transition: &Transition) -> String {
+    match transition.direction() {
+        0 => String::new(),
+        d if d > 0 => format!("head += {};", d),
+        d => format!("head = head < {0} ? 0 : head - {0};", d.unsigned_abs())
+    }
+}
+
+fn c_char(symbol: char) -> String {
+    match symbol {
+        '\'' => "'\\''".to_string(),
+        '\\' => "'\\\\'".to_string(),
+        c if c.is_ascii_graphic() || c == ' ' => format!("'{}'", c),
+        c => format!("{}", c as u32)
+    }
+}
@@ -0,0 +1,221 @@
+//! # Compiled Turing Machines
+//!
+//! An immutable execution form of a [`TuringMachine`] built for fast stepping.
+//!
+//! The transitions are flattened into a single table indexed by `state * symbols + symbol`, with
+//! each entry packed into 8 bytes and carrying a precomputed flag for whether entering its next
+//! state halts the computation.
+
+use crate::errors::TmError;
+use crate::tape::{ Tape, DenseTape };
+use crate::util::{ ComputationStatus, ComputationClock };
+use super::computation::encode_word;
+use super::machine::TuringMachine;
+
+const CONTINUE: u8 = 0;
+const ACCEPT: u8 = 1;
+const REJECT: u8 = 2;
+const UNDEFINED: u8 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Entry {
+    next_row: u32,
+    write_symbol: u16,
+    direction: i8,
+    halt: u8
+}
+
+const UNDEFINED_ENTRY: Entry = Entry { next_row: 0, write_symbol: 0, direction: 0, halt: UNDEFINED };
+
+/// A [`TuringMachine`] compiled into a flat transition table
+pub struct CompiledMachine<'a> {
+    machine: &'a TuringMachine,
+    symbols: usize,
+    table: Vec<Entry>
+}
+
+impl<'a> CompiledMachine<'a> {
+    /// Compile a machine, failing if it does not fit the compact table widths
+    pub fn compile(machine: &'a TuringMachine) -> Result<Self, TmError> {
+        let symbols = machine.view_tape_symbols().len() + machine.view_language_symbols().len();
+        let states = machine.view_states().len();
+
+        if states.saturating_mul(symbols) > u32::MAX as usize {
+            return Err(TmError::new(format!("Too many states ({}) to compile.", states)));
+        }
+
+        if symbols > u16::MAX as usize {
+            return Err(TmError::new(format!("Too many symbols ({}) to compile.", symbols)));
+        }
+
+        let (accept, reject) = (machine.view_accept_state(), machine.view_reject_state());
+        if machine.view_start_state() == accept || machine.view_start_state() == reject {
+            return Err(TmError::new(format!("The start state {} halts.", machine.view_start_state())));
+        }
+
+        // The rows of the halting states are never read, so they are left undefined
+        let mut table = vec![UNDEFINED_ENTRY; states * symbols];
+
+        for (state, row) in machine.view_transitions().iter().enumerate() {
+            let halting = state == accept || state == reject;
+            if row.len() > symbols || (!halting && row.len() < symbols) {
+                return Err(TmError::new(
+                    format!("State {} has {} transitions for {} symbols.", state, row.len(), symbols)
+                ));
+            }
+
+            for (symbol, transition) in row.iter().enumerate() {
+                if transition.next_state_id() >= states || transition.write_symbol_id() >= symbols {
+                    return Err(TmError::new(
+                        format!("Transition {} from state {} on symbol {} is out of range.", transition, state, symbol)
+                    ));
+                }
+
+                let direction = match i8::try_from(transition.direction()) {
+                    Ok(val) => val,
+                    Err(_) => return Err(TmError::new(
+                        format!("Transition {} from state {} on symbol {} moves too far to compile.", transition, state, symbol)
+                    ))
+                };
+
+                let halt = if transition.next_state_id() == machine.view_accept_state() {
+                    ACCEPT
+                } else if transition.next_state_id() == machine.view_reject_state() {
+                    REJECT
+                } else {
+                    CONTINUE
+                };
+
+                table[state * symbols + symbol] = Entry {
+                    next_row: (transition.next_state_id() * symbols) as u32,
+                    write_symbol: transition.write_symbol_id() as u16,
+                    direction,
+                    halt
+                };
+            }
+        }
+
+        if let Some(state) = (0..states).find(|state| *state != accept && *state != reject && machine.view_transitions().get(*state).is_none()) {
+            return Err(TmError::new(format!("State {} has 0 transitions for {} symbols.", state, symbols)));
+        }
+
+        Ok(CompiledMachine { machine, symbols, table })
+    }
+
+    pub fn view_machine(&self) -> &TuringMachine { self.machine }
+
+    /// Construct an unbounded computation using the compiled machine and a specified word
+    pub fn compute(&self, word: &str) -> Result<CompiledComputation<'_>, TmError> {
+        self.compute_on_tape(word, (None, None))
+    }
+
+    /// Construct a bounded computation using the compiled machine and a specified word
+    pub fn bounded_compute(&self, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<CompiledComputation<'_>, TmError> {
+        self.compute_on_tape(word, limits)
+    }
+
+    /// Construct a bounded computation using the compiled machine and a specified word, storing the tape in `T`
+    pub fn compute_on_tape<T: Tape>(&self, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<CompiledComputation<'_, T>, TmError> {
+        let symbols = encode_word(self.machine, word)?;
+        let tape = T::load(&symbols, self.symbols)?;
+
+        Ok(CompiledComputation {
+            machine: self,
+            current_row: self.machine.view_start_state() * self.symbols,
+            status: ComputationStatus::Executing,
+            clock: ComputationClock::clock(limits.0, limits.1, tape.len()),
+            tape
+        })
+    }
+}
+
+/// A computation stepping through the table of a [`CompiledMachine`]
+///
+/// Halts with exactly the same status, clock and tape as the equivalent
+/// [`Computation`](super::computation::Computation).
+pub struct CompiledComputation<'a, T: Tape = DenseTape> {
+    machine: &'a CompiledMachine<'a>,
+    current_row: usize,
+    tape: T,
+    status: ComputationStatus,
+    clock: ComputationClock
+}
+
+impl<T: Tape> CompiledComputation<'_, T> {
+    pub fn view_current_state(&self) -> usize { self.current_row / self.machine.symbols }
+    pub fn view_head_position(&self) -> usize { self.tape.head() }
+    pub fn view_tape(&self) -> &T { &self.tape }
+    pub fn view_status(&self) -> &ComputationStatus { &self.status }
+    pub fn view_clock(&self) -> &ComputationClock { &self.clock }
+
+    /// Execute the next transition from the current configuration
+    #[inline]
+    pub fn step(&mut self) {
+        if self.status != ComputationStatus::Executing {
+            return;
+        }
+
+        if let Some(status) = self.clock.tick() {
+            self.status = status;
+            return;
+        }
+
+        let read_symbol = self.tape.read();
+        let entry = self.machine.table[self.current_row + read_symbol];
+
+        self.current_row = entry.next_row as usize;
+        self.tape.write(entry.write_symbol as usize);
+
+        if self.tape.move_head(entry.direction as i32) > 0 {
+            if let Some(status) = self.clock.occupy(self.tape.len()) {
+                self.status = status;
+                return;
+            }
+        }
+
+        match entry.halt {
+            ACCEPT => self.status = ComputationStatus::Accept,
+            REJECT => self.status = ComputationStatus::Reject,
+            _ => ()
+        }
+    }
+
+    /// Run the Computation until it halts
+    ///
+    /// Keeps the current row in a local and only checks for halting where the table says it can happen.
+    pub fn run(&mut self) {
+        if self.status != ComputationStatus::Executing {
+            return;
+        }
+
+        let table = &self.machine.table;
+        let mut row = self.current_row;
+
+        loop {
+            if let Some(status) = self.clock.tick() {
+                self.status = status;
+                break;
+            }
+
+            let entry = table[row + self.tape.read()];
+
+            row = entry.next_row as usize;
+            self.tape.write(entry.write_symbol as usize);
+
+            if self.tape.move_head(entry.direction as i32) > 0 {
+                if let Some(status) = self.clock.occupy(self.tape.len()) {
+                    self.status = status;
+                    break;
+                }
+            }
+
+            match entry.halt {
+                CONTINUE => (),
+                ACCEPT => { self.status = ComputationStatus::Accept; break; },
+                _ => { self.status = ComputationStatus::Reject; break; }
+            }
+        }
+
+        self.current_row = row;
+    }
+}
@@ -0,0 +1,218 @@
+//! # Machine Composition
+//!
+//! Build new machines out of existing ones.
+//!
+//! Each combinator copies the states of its components under a prefix (`first.q0`, `second.q0`, ...)
+//! and redirects every transition into a component's accept or reject state to wherever the
+//! combinator continues, so control passes on in the same step that would have halted the component.
+//! The tape is left exactly as the previous component left it.
+//!
+//! The symbols of the components are merged by character. Every component must share the blank and
+//! start marker, and a component reading a symbol it does not know rejects.
+
+use crate::errors::TmError;
+use crate::util::{ State, Symbol, Transition };
+use super::machine::TuringMachine;
+
+/// Where control passes when a component halts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Exit {
+    Accept,
+    Reject,
+    Enter(usize)
+}
+
+struct Component<'a> {
+    prefix: &'a str,
+    machine: &'a TuringMachine,
+    on_accept: Exit,
+    on_reject: Exit
+}
+
+/// Run `first`, and when it accepts continue in the start state of `second`
+///
+/// Accepts when `second` accepts and rejects when either rejects.
+pub fn sequence(first: &TuringMachine, second: &TuringMachine) -> Result<TuringMachine, TmError> {
+    combine(&[
+        Component { prefix: "first", machine: first, on_accept: Exit::Enter(1), on_reject: Exit::Reject },
+        Component { prefix: "second", machine: second, on_accept: Exit::Accept, on_reject: Exit::Reject },
+    ])
+}
+
+/// Run `condition`, continuing in `accepted` when it accepts and in `rejected` when it rejects
+pub fn branch(condition: &TuringMachine, accepted: &TuringMachine, rejected: &TuringMachine) -> Result<TuringMachine, TmError> {
+    combine(&[
+        Component { prefix: "condition", machine: condition, on_accept: Exit::Enter(1), on_reject: Exit::Enter(2) },
+        Component { prefix: "accepted", machine: accepted, on_accept: Exit::Accept, on_reject: Exit::Reject },
+        Component { prefix: "rejected", machine: rejected, on_accept: Exit::Accept, on_reject: Exit::Reject },
+    ])
+}
+
+/// Run `body` again from its start state every time it accepts, and accept once it rejects
+pub fn repeat(body: &TuringMachine) -> Result<TuringMachine, TmError> {
+    combine(&[
+        Component { prefix: "body", machine: body, on_accept: Exit::Enter(0), on_reject: Exit::Accept },
+    ])
+}
+
+/// Run `body` while `condition` accepts, rejecting as soon as `body` rejects and accepting once `condition` rejects
+pub fn while_loop(condition: &TuringMachine, body: &TuringMachine) -> Result<TuringMachine, TmError> {
+    combine(&[
+        Component { prefix: "condition", machine: condition, on_accept: Exit::Enter(1), on_reject: Exit::Accept },
+        Component { prefix: "body", machine: body, on_accept: Exit::Enter(0), on_reject: Exit::Reject },
+    ])
+}
+
+/// Merge the alphabets of the components, keeping the blank and start marker of the first in place
+fn merge_alphabets(components: &[Component]) -> Result<(Vec<char>, Vec<char>), TmError> {
+    let first = components[0].machine;
+    if first.view_tape_symbols().len() < 2 {
+        return Err(TmError::new("Components need a blank and a start marker.".to_string()));
+    }
+
+    let mut tape_symbols: Vec<char> = vec![];
+    let mut language_symbols: Vec<char> = vec![];
+
+    for component in components {
+        let machine = component.machine;
+        if machine.view_tape_symbols().len() < 2 || machine.view_tape_symbols()[..2] != first.view_tape_symbols()[..2] {
+            return Err(TmError::new(
+                format!("Component {} does not share the blank and start marker of the first component.", component.prefix)
+            ));
+        }
+
+        for symbol in machine.view_tape_symbols() {
+            if !tape_symbols.contains(&symbol.as_char()) {
+                tape_symbols.push(symbol.as_char());
+            }
+        }
+
+        for symbol in machine.view_language_symbols() {
+            if !language_symbols.contains(&symbol.as_char()) {
+                language_symbols.push(symbol.as_char());
+            }
+        }
+    }
+
+    if let Some(symbol) = tape_symbols.iter().find(|symbol| language_symbols.contains(symbol)) {
+        return Err(TmError::new(
+            format!("Symbol {} is a tape symbol in one component and a language symbol in another.", symbol)
+        ));
+    }
+
+    Ok((tape_symbols, language_symbols))
+}
+
+/// Combine components into one machine starting in the first component
+fn combine(components: &[Component]) -> Result<TuringMachine, TmError> {
+    let (tape_symbols, language_symbols) = merge_alphabets(components)?;
+    let symbols: Vec<char> = tape_symbols.iter().chain(language_symbols.iter()).copied().collect();
+
+    // New ids of the non-halting states of every component, followed by the shared accept and reject states
+    let mut states: Vec<State> = vec![];
+    let mut state_ids: Vec<Vec<Option<usize>>> = vec![];
+
+    for component in components {
+        let machine = component.machine;
+        let start = machine.view_start_state();
+        if start == machine.view_accept_state() || start == machine.view_reject_state() {
+            return Err(TmError::new(format!("Component {} starts in a halting state.", component.prefix)));
+        }
+
+        let ids = machine.view_states().iter().enumerate().map(|(i, state)| {
+            if i == machine.view_accept_state() || i == machine.view_reject_state() {
+                None
+            } else {
+                states.push(State::new_state(format!("{}.{}", component.prefix, state)));
+                Some(states.len() - 1)
+            }
+        }).collect();
+
+        state_ids.push(ids);
+    }
+
+    let accept_state = states.len();
+    let reject_state = accept_state + 1;
+    states.push(State::new_state("accept".to_string()));
+    states.push(State::new_state("reject".to_string()));
+
+    let exit_id = |exit: Exit| -> Result<usize, TmError> {
+        match exit {
+            Exit::Accept => Ok(accept_state),
+            Exit::Reject => Ok(reject_state),
+            Exit::Enter(k) => {
+                let component = components.get(k).ok_or_else(|| TmError::new(format!("Component {} does not exist.", k)))?;
+                let start = component.machine.view_start_state();
+                match state_ids[k].get(start) {
+                    Some(Some(val)) => Ok(*val),
+                    _ => Err(TmError::new(format!("Component {} starts in missing state {}.", component.prefix, start)))
+                }
+            }
+        }
+    };
+
+    let mut transitions: Vec<Vec<Transition>> = vec![];
+    for (k, component) in components.iter().enumerate() {
+        let machine = component.machine;
+        let local_symbols: Vec<char> = machine.view_tape_symbols().iter()
+            .chain(machine.view_language_symbols().iter())
+            .map(|symbol| symbol.as_char())
+            .collect();
+
+        let resolve = |state: usize| -> Result<usize, TmError> {
+            if state == machine.view_accept_state() {
+                exit_id(component.on_accept)
+            } else if state == machine.view_reject_state() {
+                exit_id(component.on_reject)
+            } else {
+                match state_ids[k].get(state) {
+                    Some(Some(val)) => Ok(*val),
+                    _ => Err(TmError::new(format!("Component {} refers to missing state {}.", component.prefix, state)))
+                }
+            }
+        };
+
+        for (state, id) in state_ids[k].iter().enumerate() {
+            if id.is_none() {
+                continue;
+            }
+
+            let row = machine.view_transitions().get(state).map_or(&[][..], |row| &row[..]);
+            let mut merged_row = vec![];
+
+            for (symbol_id, symbol) in symbols.iter().enumerate() {
+                let transition = local_symbols.iter()
+                    .position(|local| local == symbol)
+                    .and_then(|local| row.get(local));
+
+                merged_row.push(match transition {
+                    Some(transition) => {
+                        let write = match local_symbols.get(transition.write_symbol_id()) {
+                            Some(write) => symbols.iter().position(|merged| merged == write).unwrap(),
+                            None => return Err(TmError::new(
+                                format!("Component {} writes missing symbol {}.", component.prefix, transition.write_symbol_id())
+                            ))
+                        };
+                        Transition::new_transition(resolve(transition.next_state_id())?, write, transition.direction())
+                    },
+                    None => Transition::new_transition(exit_id(component.on_reject)?, symbol_id, 0)
+                });
+            }
+
+            transitions.push(merged_row);
+        }
+    }
+
+    transitions.push(vec![]); // accept transitions
+    transitions.push(vec![]); // reject transitions
+
+    Ok(TuringMachine::new(
+        states,
+        language_symbols.into_iter().map(Symbol::new_symbol).collect(),
+        tape_symbols.into_iter().map(Symbol::new_symbol).collect(),
+        transitions,
+        exit_id(Exit::Enter(0))?,
+        accept_state,
+        reject_state
+    ))
+}
@@ -1,54 +1,65 @@
 use crate::errors::TmError;
+use crate::tape::{ Tape, DenseTape };
 use crate::util::{ ComputationStatus, ComputationClock, Symbol };
+use super::display::{ ComputationDisplay, DisplayOptions };
 use super::machine::TuringMachine;
 
-pub struct Computation<'a> {
+pub struct Computation<'a, T: Tape = DenseTape> {
     machine: &'a TuringMachine,
     current_state: usize,
-    head_position: usize,
-    tape: Vec<usize>,
+    tape: T,
     status: ComputationStatus,
     clock: ComputationClock
 }
 
-impl Computation<'_> {
-    pub fn view_machine(&self) -> &TuringMachine { &self.machine }
+impl<T: Tape> Computation<'_, T> {
+    pub fn view_machine(&self) -> &TuringMachine { self.machine }
     pub fn view_current_state(&self) -> usize { self.current_state }
-    pub fn view_head_position(&self) -> usize { self.head_position }
-    pub fn view_tape(&self) -> &Vec<usize> { &self.tape }
+    pub fn view_head_position(&self) -> usize { self.tape.head() }
+    pub fn view_tape(&self) -> &T { &self.tape }
     pub fn view_status(&self) -> &ComputationStatus { &self.status }
     pub fn view_clock(&self) -> &ComputationClock { &self.clock }
+
+    /// The output left on the tape, read from after the start marker up to the first blank
+    pub fn view_output(&self) -> String {
+        let symbols = self.machine.symbol_characters();
+
+        (1..).map(|position| self.tape.get(position))
+            .take_while(|id| *id != 0)
+            .map(|id| symbols[id])
+            .collect()
+    }
+
+    /// The number of cells worth showing, up to the last cell that is not blank and at least up to
+    /// the head
+    pub fn view_extent(&self) -> usize {
+        let written = self.tape.to_vec().iter().rposition(|id| *id != 0).map_or(0, |last| last + 1);
+        written.max(self.tape.head() + 1)
+    }
 }
 
-impl<'a> Computation<'a> {
+impl<'a> Computation<'a, DenseTape> {
     /// Create a computation from a machine and word
-    pub fn start(machine: &'a TuringMachine, word: &String) -> Result<Self, TmError> {
+    pub fn start(machine: &'a TuringMachine, word: &str) -> Result<Self, TmError> {
         Self::bounded_start(machine, word, (None, None))
     }
 
     /// Create a bounded computation from a machine and word
-    pub fn bounded_start(machine: &'a TuringMachine, word: &String, limits: (Option<usize>, Option<usize>)) -> Result<Self, TmError> {
-        let mut tape = vec![1];
-
-        for (i, w) in word.chars().enumerate() {
-            let symbol = Symbol::new_symbol(w);
-            let index = match machine.view_language_symbols().iter().position(|x| { x == &symbol }) {
-                Some(val) => val + machine.view_tape_symbols().len(),
-                None => match machine.view_tape_symbols().iter().position(|x| { x == &symbol }) {
-                    Some(val) => val,
-                    None => return Err(TmError::new(
-                        format!("Invalid symbol {} found at position {}.", w, i)
-                    ))
-                }
-            };
-
-            tape.push(index);
-        }
+    pub fn bounded_start(machine: &'a TuringMachine, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<Self, TmError> {
+        Self::start_on_tape(machine, word, limits)
+    }
+}
+
+impl<'a, T: Tape> Computation<'a, T> {
+    /// Create a bounded computation from a machine and word, storing the tape in `T`
+    pub fn start_on_tape(machine: &'a TuringMachine, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<Self, TmError> {
+        let symbols = encode_word(machine, word)?;
+        let alphabet_size = machine.view_tape_symbols().len() + machine.view_language_symbols().len();
+        let tape = T::load(&symbols, alphabet_size)?;
 
         Ok(Computation {
             machine,
             current_state: machine.view_start_state(),
-            head_position: 0,
             status: ComputationStatus::Executing,
             clock: ComputationClock::clock(limits.0, limits.1, tape.len()),
             tape
@@ -57,57 +68,47 @@ impl<'a> Computation<'a> {
 
     /// Execute the next transition from the current configuration
     pub fn step(&mut self) {
-        match self.status {
-            ComputationStatus::Executing => (),
-            _ => return
+        if self.status != ComputationStatus::Executing {
+            return;
         }
 
-        self.clock.time += 1;
-        match self.clock.max_time {
-            Some(limit) => if self.clock.time >= limit {
-                self.status = ComputationStatus::Timeout;
-                return;
-            },
-            None => ()
+        if let Some(status) = self.clock.tick() {
+            self.status = status;
+            return;
         }
 
-        let read_symbol = self.tape[self.head_position];
+        let read_symbol = self.tape.read();
         let transition = &self.machine.view_transitions()[self.current_state][read_symbol];
 
-        let new_head_position = match transition.direction().is_negative() {
-            false => match self.head_position.checked_add(transition.direction() as usize) {
-                Some(val) => val,
-                None => panic!("Head position exceded max usize")
-            },
-            true => match self.head_position.checked_sub(transition.direction().wrapping_abs() as usize) {
-                Some(val) => val,
-                None => 0
-            }
-        };
-
         self.current_state = transition.next_state_id();
-        self.tape[self.head_position] = transition.write_symbol_id();
-        self.head_position = new_head_position;
-
-        if self.head_position >= self.tape.len() {
-            self.tape.push(0 as usize);
-            self.clock.space += 1;
-
-            match self.clock.max_space {
-                Some(limit) => if self.clock.space >= limit {
-                    self.status = ComputationStatus::Spaceout;
-                    return;
-                },
-                None => ()
+        self.tape.write(transition.write_symbol_id());
+
+        if self.tape.move_head(transition.direction()) > 0 {
+            if let Some(status) = self.clock.occupy(self.tape.len()) {
+                self.status = status;
+                return;
             }
         }
 
         if transition.next_state_id() == self.machine.view_accept_state() {
             self.status = ComputationStatus::Accept;
-            return;
         } else if transition.next_state_id() == self.machine.view_reject_state() {
             self.status = ComputationStatus::Reject;
-            return;
+        }
+    }
+
+    /// Format the configuration with a window around the head or ANSI colors
+    pub fn display(&self, options: DisplayOptions) -> ComputationDisplay<'_, 'a, T> {
+        ComputationDisplay { computation: self, options }
+    }
+
+    /// Continue in another state without taking a step, as when an oracle answers a query
+    pub(crate) fn jump(&mut self, state: usize) {
+        self.current_state = state;
+        if state == self.machine.view_accept_state() {
+            self.status = ComputationStatus::Accept;
+        } else if state == self.machine.view_reject_state() {
+            self.status = ComputationStatus::Reject;
         }
     }
 
@@ -117,4 +118,31 @@ impl<'a> Computation<'a> {
             self.step();
         }
     }
-}
\ No newline at end of file
+}
+
+/// Convert a word into the initial tape contents of a machine, starting with the start marker
+pub(crate) fn encode_word(machine: &TuringMachine, word: &str) -> Result<Vec<usize>, TmError> {
+    encode_symbols(machine.view_tape_symbols(), machine.view_language_symbols(), word)
+}
+
+/// Convert a word into initial tape contents over tape and language symbols, starting with the start marker
+pub(crate) fn encode_symbols(tape_symbols: &[Symbol], language_symbols: &[Symbol], word: &str) -> Result<Vec<usize>, TmError> {
+    let mut tape = vec![1];
+
+    for (i, w) in word.chars().enumerate() {
+        let symbol = Symbol::new_symbol(w);
+        let index = match language_symbols.iter().position(|x| { x == &symbol }) {
+            Some(val) => val + tape_symbols.len(),
+            None => match tape_symbols.iter().position(|x| { x == &symbol }) {
+                Some(val) => val,
+                None => return Err(TmError::new(
+                    format!("Invalid symbol {} found at position {}.", w, i)
+                ))
+            }
+        };
+
+        tape.push(index);
+    }
+
+    Ok(tape)
+}
@@ -0,0 +1,172 @@
+//! # Space-Time Diagrams
+//!
+//! Picture a run of a machine with one row per step and one column per tape cell, coloured by
+//! symbol with the head marked in red. Diagrams are written as SVG or as binary PPM images.
+//!
+//! Long runs are kept manageable by recording only every `n`th step and only a window of cells,
+//! so a million step run sampled every thousandth step is a thousand rows.
+
+use crate::tape::Tape;
+use crate::util::ComputationStatus;
+use super::computation::Computation;
+
+/// Which steps and cells of a run to record, and how large to draw them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagramOptions {
+    /// Record the configuration after every `every`th step, as well as the first and last
+    pub every: usize,
+    /// Record only the cells `start..end`, instead of every cell the tape ever uses
+    pub cells: Option<(usize, usize)>,
+    /// The width and height in pixels of a cell
+    pub scale: usize
+}
+
+impl Default for DiagramOptions {
+    fn default() -> Self {
+        Self { every: 1, cells: None, scale: 4 }
+    }
+}
+
+/// The recorded rows of a run, each with the step it was recorded at and the head position
+pub struct SpaceTimeDiagram {
+    rows: Vec<Vec<usize>>,
+    heads: Vec<usize>,
+    steps: Vec<usize>,
+    first_cell: usize,
+    scale: usize
+}
+
+impl SpaceTimeDiagram {
+    /// Run a computation until it halts, recording its configurations
+    pub fn record<T: Tape>(computation: &mut Computation<'_, T>, options: &DiagramOptions) -> Self {
+        let every = options.every.max(1);
+        let mut diagram = SpaceTimeDiagram {
+            rows: vec![],
+            heads: vec![],
+            steps: vec![],
+            first_cell: options.cells.map_or(0, |(start, _)| start),
+            scale: options.scale.max(1)
+        };
+
+        diagram.sample(computation, options);
+        while computation.view_status() == &ComputationStatus::Executing {
+            computation.step();
+            let time = computation.view_clock().time;
+            if time.is_multiple_of(every) || computation.view_status() != &ComputationStatus::Executing {
+                diagram.sample(computation, options);
+            }
+        }
+
+        // Rows recorded before the tape grew to its full extent are padded with blanks
+        let width = diagram.rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        for row in diagram.rows.iter_mut() {
+            row.resize(width, 0);
+        }
+
+        diagram
+    }
+
+    fn sample<T: Tape>(&mut self, computation: &Computation<'_, T>, options: &DiagramOptions) {
+        let tape = computation.view_tape();
+        let (start, end) = options.cells.unwrap_or((0, tape.len()));
+
+        self.rows.push(tape.window(start, end.max(start)).collect());
+        self.heads.push(tape.head());
+        self.steps.push(computation.view_clock().time);
+    }
+
+    pub fn view_rows(&self) -> &Vec<Vec<usize>> { &self.rows }
+    pub fn view_heads(&self) -> &Vec<usize> { &self.heads }
+    pub fn view_steps(&self) -> &Vec<usize> { &self.steps }
+
+    /// The number of cells in every row
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, |row| row.len())
+    }
+
+    /// The column of the head in a row, if the head is inside the recorded window
+    fn head_column(&self, row: usize) -> Option<usize> {
+        self.heads[row].checked_sub(self.first_cell).filter(|column| *column < self.width())
+    }
+
+    /// Write the diagram as an SVG image, merging runs of equal symbols into one rectangle
+    pub fn to_svg(&self) -> String {
+        let s = self.scale;
+        let (width, height) = (self.width() * s, self.rows.len() * s);
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" shape-rendering=\"crispEdges\">\n");
+        svg.push_str(&format!("  <rect width=\"{width}\" height=\"{height}\" fill=\"{}\"/>\n", hex(colour(0))));
+
+        for (y, row) in self.rows.iter().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let run = row[x..].iter().take_while(|symbol| **symbol == row[x]).count();
+                if row[x] != 0 {
+                    svg.push_str(&format!("  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{s}\" fill=\"{}\"/>\n", x * s, y * s, run * s, hex(colour(row[x]))));
+                }
+                x += run;
+            }
+
+            if let Some(column) = self.head_column(y) {
+                svg.push_str(&format!("  <rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"none\" stroke=\"red\"/>\n", column * s, y * s));
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Write the diagram as a binary PPM image, outlining the head or filling it when cells are
+    /// too small to outline
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let s = self.scale;
+        let (width, height) = (self.width() * s, self.rows.len() * s);
+        let mut image = format!("P6\n{width} {height}\n255\n").into_bytes();
+
+        for (y, row) in self.rows.iter().enumerate() {
+            let head = self.head_column(y);
+            for dy in 0..s {
+                for (x, symbol) in row.iter().enumerate() {
+                    for dx in 0..s {
+                        let edge = s < 3 || dx == 0 || dy == 0 || dx == s - 1 || dy == s - 1;
+                        let (r, g, b) = if head == Some(x) && edge { HEAD } else { colour(*symbol) };
+                        image.extend_from_slice(&[r, g, b]);
+                    }
+                }
+            }
+        }
+
+        image
+    }
+}
+
+const HEAD: (u8, u8, u8) = (230, 30, 30);
+
+/// The colour of a symbol: white for the blank, dark grey for the start marker, and evenly spread
+/// hues for every other symbol
+fn colour(symbol: usize) -> (u8, u8, u8) {
+    match symbol {
+        0 => (255, 255, 255),
+        1 => (64, 64, 64),
+        _ => {
+            let hue = ((symbol - 2) as f64 * 0.618_033_988_75).fract() * 6.0;
+            let (value, saturation) = (0.85, 0.6);
+            let chroma = value * saturation;
+            let second = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+            let (r, g, b) = match hue as usize {
+                0 => (chroma, second, 0.0),
+                1 => (second, chroma, 0.0),
+                2 => (0.0, chroma, second),
+                3 => (0.0, second, chroma),
+                4 => (second, 0.0, chroma),
+                _ => (chroma, 0.0, second)
+            };
+            let m = value - chroma;
+            let byte = |channel: f64| ((channel + m) * 255.0).round() as u8;
+            (byte(r), byte(g), byte(b))
+        }
+    }
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
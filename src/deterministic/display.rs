@@ -0,0 +1,79 @@
+//! # Computation Display
+//!
+//! Format the configuration of a computation on two lines, the current state followed by the tape
+//! contents, and a caret under the cell the head is on:
+//!
+//! ```text
+//! to_end: >11+1
+//!           ^
+//! ```
+//!
+//! Blanks after the last written cell are trimmed unless the head is on them. A window shows only
+//! the cells around the head, with `…` marking the cells cut off on either side.
+
+use std::fmt;
+
+use crate::tape::Tape;
+use super::computation::Computation;
+
+const BOLD: &str = "\x1b[1m";
+const REVERSE: &str = "\x1b[7m";
+const RESET: &str = "\x1b[0m";
+
+/// Choices for formatting a computation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DisplayOptions {
+    /// Show only this many cells on either side of the head
+    pub window: Option<usize>,
+    /// Embolden the state and highlight the head cell with ANSI escape codes
+    pub color: bool
+}
+
+/// A computation formatted with [`DisplayOptions`], created by [`Computation::display`]
+pub struct ComputationDisplay<'c, 'a, T: Tape> {
+    pub(crate) computation: &'c Computation<'a, T>,
+    pub(crate) options: DisplayOptions
+}
+
+impl<T: Tape> fmt::Display for ComputationDisplay<'_, '_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let machine = self.computation.view_machine();
+        let symbols = machine.symbol_characters();
+
+        let tape = self.computation.view_tape();
+        let head = tape.head();
+        let extent = self.computation.view_extent();
+        let (start, end) = match self.options.window {
+            Some(window) => (head.saturating_sub(window), extent.min(head + window + 1)),
+            None => (0, extent)
+        };
+
+        let state = machine.view_states()[self.computation.view_current_state()].as_str();
+        let left = if start > 0 { "…" } else { "" };
+        let right = if end < extent { "…" } else { "" };
+
+        if self.options.color {
+            write!(f, "{BOLD}{state}{RESET}: {left}")?;
+        } else {
+            write!(f, "{state}: {left}")?;
+        }
+
+        for position in start..end {
+            let symbol = symbols[tape.get(position)];
+            if self.options.color && position == head {
+                write!(f, "{REVERSE}{symbol}{RESET}")?;
+            } else {
+                write!(f, "{symbol}")?;
+            }
+        }
+
+        let offset = state.chars().count() + 2 + left.chars().count() + head - start;
+        write!(f, "{right}\n{}^", " ".repeat(offset))
+    }
+}
+
+impl<T: Tape> fmt::Display for Computation<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.display(DisplayOptions::default()).fmt(f)
+    }
+}
@@ -0,0 +1,108 @@
+//! # Graphviz Export
+//!
+//! Render the state diagram of a machine in the DOT language. Every state is a node, drawn as a
+//! double circle for the accept state, a grey octagon for the reject state, and with an arrow in
+//! from nowhere for the start state. Transitions between the same pair of states share one edge,
+//! labelled `a→b,R1; c→d,L1` with the symbol read, the symbol written and the move of each.
+
+use std::collections::HashSet;
+
+use crate::errors::TmError;
+use crate::util::{ ComputationStatus, Transition };
+use super::machine::TuringMachine;
+
+/// Choices for rendering a state diagram
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DotOptions {
+    /// Leave out the reject state and every transition into it
+    pub hide_reject: bool,
+    /// A sequence of states, as recorded by [`trace`], whose states and edges are highlighted
+    pub highlight: Vec<usize>
+}
+
+/// The states a machine passes through on a word, starting with the start state
+pub fn trace(machine: &TuringMachine, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<Vec<usize>, TmError> {
+    let mut computation = machine.bounded_compute(word, limits)?;
+    let mut states = vec![computation.view_current_state()];
+
+    while computation.view_status() == &ComputationStatus::Executing {
+        computation.step();
+        if computation.view_status() != &ComputationStatus::Timeout {
+            states.push(computation.view_current_state());
+        }
+    }
+
+    Ok(states)
+}
+
+/// Quote a name for use as a DOT string
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub(crate) fn render(machine: &TuringMachine, options: &DotOptions) -> String {
+    let symbols = machine.symbol_characters();
+    let (accept, reject) = (machine.view_accept_state(), machine.view_reject_state());
+    let visited: HashSet<usize> = options.highlight.iter().copied().collect();
+    let travelled: HashSet<(usize, usize)> = options.highlight.windows(2).map(|pair| (pair[0], pair[1])).collect();
+    let highlight = ", color=red, penwidth=2";
+
+    let mut dot = String::from("digraph {\n    rankdir=LR;\n    node [shape=circle];\n    start [shape=point];\n");
+
+    for (state, name) in machine.view_states().iter().enumerate() {
+        if state == reject && options.hide_reject {
+            continue;
+        }
+
+        let mut style = match state {
+            state if state == accept => ", shape=doublecircle".to_string(),
+            state if state == reject => ", shape=octagon, style=filled, fillcolor=lightgrey".to_string(),
+            _ => String::new()
+        };
+        if visited.contains(&state) {
+            style.push_str(highlight);
+        }
+        dot.push_str(&format!("    q{} [label={}{}];\n", state, quote(name.as_str()), style));
+    }
+
+    dot.push_str(&format!("    start -> q{};\n", machine.view_start_state()));
+
+    let label = |read: usize, transition: &Transition| {
+        format!("{}→{},{}", symbols[read], symbols[transition.write_symbol_id()], transition.direction_to_string())
+    };
+    for (state, next, labels) in edges(machine, options.hide_reject, label) {
+        let style = if travelled.contains(&(state, next)) { highlight } else { "" };
+        dot.push_str(&format!("    q{} -> q{} [label={}{}];\n", state, next, quote(&labels.join("; ")), style));
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// The edges of the state diagram as `(from, to, labels)`, with every transition between the same
+/// pair of states labelled on one edge, leaving out transitions into the reject state when asked
+pub(crate) fn edges(machine: &TuringMachine, hide_reject: bool, label: impl Fn(usize, &Transition) -> String) -> Vec<(usize, usize, Vec<String>)> {
+    let (accept, reject) = (machine.view_accept_state(), machine.view_reject_state());
+    let mut edges: Vec<(usize, usize, Vec<String>)> = vec![];
+
+    for (state, row) in machine.view_transitions().iter().enumerate() {
+        if state == accept || state == reject {
+            continue;
+        }
+
+        let first = edges.len();
+        for (read, transition) in row.iter().enumerate() {
+            let next = transition.next_state_id();
+            if next == reject && hide_reject {
+                continue;
+            }
+
+            match edges[first..].iter_mut().find(|(_, to, _)| *to == next) {
+                Some((_, _, labels)) => labels.push(label(read, transition)),
+                None => edges.push((state, next, vec![label(read, transition)]))
+            }
+        }
+    }
+
+    edges
+}
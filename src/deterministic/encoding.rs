@@ -0,0 +1,102 @@
+//! # Number Encodings
+//!
+//! Conventions for writing natural numbers and tuples of them as words, used to prepare the input
+//! of a [`Transducer`](super::transducer::Transducer) and to read back its output.
+
+use crate::errors::TmError;
+
+/// Writes natural numbers as words and reads them back
+///
+/// Implement this for encodings beyond [`Unary`] and [`Positional`].
+pub trait Encoding {
+    fn encode(&self, value: u64) -> String;
+    fn decode(&self, text: &str) -> Result<u64, TmError>;
+}
+
+/// The number `n` written as `n` copies of a symbol, so zero is the empty word
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unary(pub char);
+
+impl Encoding for Unary {
+    fn encode(&self, value: u64) -> String {
+        std::iter::repeat_n(self.0, value as usize).collect()
+    }
+
+    fn decode(&self, text: &str) -> Result<u64, TmError> {
+        match text.chars().position(|c| c != self.0) {
+            Some(i) => Err(TmError::new(format!("Invalid unary digit at position {} of {}.", i, text))),
+            None => Ok(text.chars().count() as u64)
+        }
+    }
+}
+
+/// Numbers written in the base given by the number of digits, most significant digit first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Positional {
+    digits: Vec<char>
+}
+
+impl Positional {
+    /// Create an encoding where `digits[d]` stands for the digit `d`
+    pub fn new(digits: &[char]) -> Result<Self, TmError> {
+        if digits.len() < 2 {
+            return Err(TmError::new("A positional encoding needs at least two digits.".to_string()));
+        }
+
+        for (i, digit) in digits.iter().enumerate() {
+            if digits[..i].contains(digit) {
+                return Err(TmError::new(format!("Digit {} appears more than once.", digit)));
+            }
+        }
+
+        Ok(Self { digits: digits.to_vec() })
+    }
+
+    /// Binary written with `0` and `1`
+    pub fn binary() -> Self {
+        Self { digits: vec!['0', '1'] }
+    }
+
+    pub fn view_digits(&self) -> &Vec<char> { &self.digits }
+}
+
+impl Encoding for Positional {
+    fn encode(&self, mut value: u64) -> String {
+        let base = self.digits.len() as u64;
+        let mut text = vec![self.digits[(value % base) as usize]];
+        while value >= base {
+            value /= base;
+            text.push(self.digits[(value % base) as usize]);
+        }
+
+        text.into_iter().rev().collect()
+    }
+
+    fn decode(&self, text: &str) -> Result<u64, TmError> {
+        if text.is_empty() {
+            return Err(TmError::new("A positional number needs at least one digit.".to_string()));
+        }
+
+        let base = self.digits.len() as u64;
+        text.chars().enumerate().try_fold(0u64, |value, (i, c)| {
+            let digit = match self.digits.iter().position(|digit| *digit == c) {
+                Some(val) => val as u64,
+                None => return Err(TmError::new(format!("Invalid digit {} at position {} of {}.", c, i, text)))
+            };
+
+            value.checked_mul(base)
+                .and_then(|value| value.checked_add(digit))
+                .ok_or_else(|| TmError::new(format!("The number {} does not fit in 64 bits.", text)))
+        })
+    }
+}
+
+/// Write a tuple of numbers with `separator` between its components
+pub fn encode_tuple(values: &[u64], separator: char, encoding: &dyn Encoding) -> String {
+    values.iter().map(|value| encoding.encode(*value)).collect::<Vec<String>>().join(&separator.to_string())
+}
+
+/// Read a tuple of numbers whose components are separated by any of `separators`
+pub fn decode_tuple(text: &str, separators: &[char], encoding: &dyn Encoding) -> Result<Vec<u64>, TmError> {
+    text.split(|c| separators.contains(&c)).map(|component| encoding.decode(component)).collect()
+}
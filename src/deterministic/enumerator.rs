@@ -0,0 +1,282 @@
+//! # Enumerators
+//!
+//! A machine with an attached printer, as defined by Sipser. The machine starts on an empty tape,
+//! and every time it enters its print state the printer outputs the work region of the tape, the
+//! cells after the start marker up to the first blank. The language of an enumerator is the set of
+//! strings it eventually prints.
+//!
+//! Both directions of Sipser's equivalence are constructed as machines: an enumerator converts
+//! into a recognizer for its language, and a decider converts into an enumerator printing its
+//! language in shortlex order. Both constructions keep two tracks on one tape, pairing every cell
+//! with a character from the Unicode private use area.
+
+use crate::errors::TmError;
+use crate::util::ComputationStatus;
+use super::builder::Builder;
+use super::computation::Computation;
+use super::machine::TuringMachine;
+
+pub struct Enumerator {
+    machine: TuringMachine,
+    print_state: usize
+}
+
+impl Enumerator {
+    /// Create an enumerator printing whenever `machine` enters `print_state`
+    pub fn new(machine: TuringMachine, print_state: usize) -> Result<Self, TmError> {
+        machine.validate()?;
+
+        if print_state >= machine.view_states().len() {
+            return Err(TmError::new(format!("State {} does not exist.", print_state)));
+        }
+
+        if print_state == machine.view_accept_state() || print_state == machine.view_reject_state() {
+            return Err(TmError::new("The print state cannot be a halting state.".to_string()));
+        }
+
+        Ok(Self { machine, print_state })
+    }
+
+    /// Lazily yield the printed strings, stopping once the enumerator halts or `max_time` is reached
+    pub fn enumerate(&self, max_time: Option<usize>) -> Result<Enumeration<'_>, TmError> {
+        Ok(Enumeration { enumerator: self, computation: self.machine.bounded_compute("", (max_time, None))? })
+    }
+
+    pub fn view_machine(&self) -> &TuringMachine { &self.machine }
+    pub fn view_print_state(&self) -> usize { self.print_state }
+}
+
+pub struct Enumeration<'a> {
+    enumerator: &'a Enumerator,
+    computation: Computation<'a>
+}
+
+impl Enumeration<'_> {
+    pub fn view_enumerator(&self) -> &Enumerator { self.enumerator }
+    pub fn view_computation(&self) -> &Computation<'_> { &self.computation }
+    pub fn view_status(&self) -> &ComputationStatus { self.computation.view_status() }
+
+    /// The work region of the tape, from after the start marker up to the first blank
+    fn region(&self) -> String {
+        self.computation.view_output()
+    }
+}
+
+impl Iterator for Enumeration<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        while self.computation.view_status() == &ComputationStatus::Executing {
+            self.computation.step();
+            if self.computation.view_status() == &ComputationStatus::Executing && self.computation.view_current_state() == self.enumerator.print_state {
+                return Some(self.region());
+            }
+        }
+
+        None
+    }
+}
+
+impl Enumerator {
+    /// Convert a decider into an enumerator printing the words it accepts in shortlex order
+    ///
+    /// Every cell holds a word track and a work track. The enumerator writes each word in turn on
+    /// both tracks and runs the decider on the work track. When the decider halts the work track is
+    /// wiped, leaving just the word, which is printed if the decider accepted before moving on to
+    /// the next word. The decider may only move one cell at a time, and the enumerator runs forever
+    /// on the first word the decider does not halt on.
+    pub fn from_decider(decider: &TuringMachine) -> Result<Self, TmError> {
+        decider.validate()?;
+        if decider.view_transitions().iter().flatten().any(|t| t.direction().abs() > 1) {
+            return Err(TmError::new("The decider may only move one cell at a time.".to_string()));
+        }
+
+        let symbols = decider.symbol_characters();
+        let language: Vec<usize> = (decider.view_tape_symbols().len()..symbols.len()).collect();
+        if language.is_empty() {
+            return Err(TmError::new("The decider needs at least one language symbol to enumerate.".to_string()));
+        }
+
+        // A cell pairs a word symbol with a work symbol. It is written as the plain word symbol when
+        // both agree, except for blanks, so a plain blank is a cell the decider has never visited
+        let words: Vec<usize> = [0, 1].into_iter().chain(language.iter().copied()).collect();
+        let mut fresh = ('\u{E000}'..='\u{F8FF}').filter(|c| !symbols.contains(c));
+        let mut cells: Vec<(char, usize, usize)> = vec![(symbols[0], 0, 0)];
+        for word in &words {
+            for work in 0..symbols.len() {
+                let character = if word == &work && *word != 0 { Some(symbols[*word]) } else { fresh.next() };
+                match character {
+                    Some(character) => cells.push((character, *word, work)),
+                    None => return Err(TmError::new("Too many symbols to pair every word and work symbol.".to_string()))
+                }
+            }
+        }
+        let cell = |word: usize, work: usize| cells[1..].iter().find(|(_, a, b)| *a == word && *b == work).unwrap().0;
+
+        let tape_symbols: Vec<char> = [symbols[0], symbols[1]].into_iter()
+            .chain(cells.iter().map(|(c, _, _)| *c).filter(|c| !symbols.contains(c)))
+            .collect();
+        let language_symbols: Vec<char> = language.iter().map(|id| symbols[*id]).collect();
+        let mut b = Builder::new(&tape_symbols, &language_symbols);
+
+        let (blank, start) = (symbols[0].to_string(), symbols[1].to_string());
+        let word: String = language_symbols.iter().collect();
+        let target = |state: usize| if state == decider.view_accept_state() {
+            "restore_accept".to_string()
+        } else if state == decider.view_reject_state() {
+            "restore_reject".to_string()
+        } else {
+            format!("decider.{}", decider.view_states()[state])
+        };
+
+        // Return to the start marker and run the decider on the current word
+        b.rule("start", &word, None, -1, "start");
+        b.rule("start", &start, None, 0, &target(decider.view_start_state()));
+
+        for state in 0..decider.view_states().len() {
+            if state == decider.view_accept_state() || state == decider.view_reject_state() {
+                continue;
+            }
+
+            for (read, word, work) in &cells {
+                let transition = &decider.view_transitions()[state][*work];
+                b.rule(&target(state), &read.to_string(), Some(cell(*word, transition.write_symbol_id())), transition.direction(), &target(transition.next_state_id()));
+            }
+        }
+
+        // Wipe the work track, printing the word if the decider accepted
+        for (outcome, next) in [("accept", "print"), ("reject", "increment")] {
+            let (restore, sweep, home) = (format!("restore_{outcome}"), format!("sweep_{outcome}"), format!("home_{outcome}"));
+            for (read, word, _) in &cells {
+                if *word == 1 {
+                    b.rule(&restore, &read.to_string(), Some(symbols[1]), 1, &sweep);
+                } else {
+                    b.rule(&restore, &read.to_string(), None, -1, &restore);
+                }
+            }
+
+            for (read, word, work) in cells.iter().filter(|(_, word, _)| *word != 1) {
+                if (*word, *work) == (0, 0) && *read == symbols[0] {
+                    b.rule(&sweep, &blank, None, -1, &home);
+                } else {
+                    b.rule(&sweep, &read.to_string(), Some(symbols[*word]), 1, &sweep);
+                }
+            }
+
+            b.rule(&home, &format!("{blank}{word}"), None, -1, &home);
+            b.rule(&home, &start, None, 0, next);
+        }
+        b.rule("print", &start, None, 0, "increment");
+
+        // Advance to the next word in shortlex order
+        let (first, last) = (language_symbols[0], language_symbols[language_symbols.len() - 1]);
+        b.rule("increment", &format!("{start}{word}"), None, 1, "increment");
+        b.rule("increment", &blank, None, -1, "carry");
+        for pair in language_symbols.windows(2) {
+            b.rule("carry", &pair[0].to_string(), Some(pair[1]), -1, "start");
+        }
+        b.rule("carry", &last.to_string(), Some(first), -1, "carry");
+        b.rule("carry", &start, None, 1, "append");
+        b.rule("append", &word, None, 1, "append");
+        b.rule("append", &blank, Some(first), -1, "start");
+
+        let print_state = b.state("print");
+        Enumerator::new(b.build(), print_state)
+    }
+
+    /// Convert the enumerator into a machine recognizing the language it prints
+    ///
+    /// Every cell holds an input track, a work track, and a mark. The recognizer runs the enumerator
+    /// on the work track, and each time it would print, marks the head, compares the work region
+    /// with the input, and returns to the mark. It accepts on a match and rejects when the enumerator
+    /// halts, so it runs forever on a word that is never printed by an enumerator that never halts.
+    pub fn to_recognizer(&self) -> Result<TuringMachine, TmError> {
+        let machine = &self.machine;
+        let symbols = machine.symbol_characters();
+        let language: Vec<usize> = (machine.view_tape_symbols().len()..symbols.len()).collect();
+
+        // A cell is written as the plain input symbol while the work track is blank and unmarked,
+        // and the start marker stays plain while the work track holds the start marker
+        let inputs: Vec<usize> = [0, 1].into_iter().chain(language.iter().copied()).collect();
+        let mut fresh = ('\u{E000}'..='\u{F8FF}').filter(|c| !symbols.contains(c));
+        let mut cells: Vec<(char, usize, usize, bool)> = vec![];
+        for input in &inputs {
+            for work in 0..symbols.len() {
+                for mark in [false, true] {
+                    let plain = !mark && ((*input != 1 && work == 0) || (*input == 1 && work == 1));
+                    let character = if plain { Some(symbols[*input]) } else { fresh.next() };
+                    match character {
+                        Some(character) => cells.push((character, *input, work, mark)),
+                        None => return Err(TmError::new("Too many symbols to pair every input and work symbol.".to_string()))
+                    }
+                }
+            }
+        }
+        let cell = |input: usize, work: usize, mark: bool| cells.iter().find(|(_, a, b, m)| *a == input && *b == work && *m == mark).unwrap().0;
+
+        let plain: Vec<char> = inputs.iter().filter(|input| **input < 2).map(|input| symbols[*input]).collect();
+        let tape_symbols: Vec<char> = plain.into_iter()
+            .chain(cells.iter().map(|(c, _, _, _)| *c).filter(|c| !symbols.contains(c)))
+            .collect();
+        let language_symbols: Vec<char> = language.iter().map(|id| symbols[*id]).collect();
+        let mut b = Builder::new(&tape_symbols, &language_symbols);
+
+        let printing = format!("enumerator.{}", machine.view_states()[self.print_state]);
+        let target = |state: usize| if state == machine.view_accept_state() || state == machine.view_reject_state() {
+            "reject".to_string()
+        } else if state == self.print_state {
+            "compare".to_string()
+        } else {
+            format!("enumerator.{}", machine.view_states()[state])
+        };
+
+        b.rule("start", &symbols[1].to_string(), None, 0, &format!("enumerator.{}", machine.view_states()[machine.view_start_state()]));
+
+        for state in 0..machine.view_states().len() {
+            if state == machine.view_accept_state() || state == machine.view_reject_state() {
+                continue;
+            }
+
+            for (read, input, work, _) in cells.iter().filter(|(_, _, _, mark)| !mark) {
+                let transition = &machine.view_transitions()[state][*work];
+                b.rule(&format!("enumerator.{}", machine.view_states()[state]), &read.to_string(), Some(cell(*input, transition.write_symbol_id(), false)), transition.direction(), &target(transition.next_state_id()));
+            }
+        }
+
+        // Mark the head and compare the work region with the input from the start
+        for (read, input, work, mark) in &cells {
+            let read = read.to_string();
+            if !mark {
+                b.rule("compare", &read, Some(cell(*input, *work, true)), 0, "compare_rewind");
+            }
+
+            if *input == 1 {
+                b.rule("compare_rewind", &read, None, 1, "compare_scan");
+                if *mark {
+                    b.rule("compare_return", &read, Some(cell(*input, *work, false)), 0, &printing);
+                } else {
+                    b.rule("compare_return", &read, None, 1, "compare_find");
+                }
+                continue;
+            }
+
+            b.rule("compare_rewind", &read, None, -1, "compare_rewind");
+            b.rule("compare_return", &read, None, -1, "compare_return");
+            if *mark {
+                b.rule("compare_find", &read, Some(cell(*input, *work, false)), 0, &printing);
+            } else {
+                b.rule("compare_find", &read, None, 1, "compare_find");
+            }
+
+            if (*input, *work) == (0, 0) {
+                b.rule("compare_scan", &read, None, 0, "accept");
+            } else if input == work {
+                b.rule("compare_scan", &read, None, 1, "compare_scan");
+            } else {
+                b.rule("compare_scan", &read, None, 0, "compare_return");
+            }
+        }
+
+        Ok(b.build())
+    }
+}
@@ -0,0 +1,200 @@
+//! # Machine Files
+//!
+//! A plain text format for machines, one declaration or rule per line:
+//!
+//! ```text
+//! # Accept words over 0 and 1 that end in 0
+//! tape _ >
+//! language 0 1
+//! states start accept reject scan last
+//! start start
+//! accept accept
+//! reject reject
+//! start > -> scan > R
+//! scan 0 -> scan 0 R
+//! scan 1 -> scan 1 R
+//! scan _ -> last _ L
+//! last 0 -> accept 0 S
+//! ```
+//!
+//! * `tape` - the tape symbols, starting with the blank and the start marker
+//! * `language` - the language symbols
+//! * `states` - optionally, the states in order, otherwise states are numbered as they appear
+//! * `start`, `accept`, `reject` - the special states
+//! * `state read -> next write move` - a rule, where the move is `S`, `L`, `R`, or `L`/`R` followed
+//!   by a number of cells
+//!
+//! A state that does not halt rejects without moving on every symbol it has no rule for. Blank
+//! lines and lines starting with `#` are ignored. Symbols are single characters other than
+//! whitespace, and state names may not contain whitespace.
+
+use std::collections::HashMap;
+
+use crate::errors::TmError;
+use crate::util::{ State, Symbol, Transition };
+use super::machine::TuringMachine;
+
+/// Parse a machine file
+pub fn from_text(text: &str) -> Result<TuringMachine, TmError> {
+    let mut tape_symbols: Option<Vec<char>> = None;
+    let mut language_symbols: Option<Vec<char>> = None;
+    let mut names: Vec<String> = vec![];
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    let mut special: [Option<usize>; 3] = [None; 3];
+    let mut rules: Vec<(usize, usize, char, String, char, i32)> = vec![];
+
+    let mut state = |name: &str, names: &mut Vec<String>| -> usize {
+        *ids.entry(name.to_string()).or_insert_with(|| {
+            names.push(name.to_string());
+            names.len() - 1
+        })
+    };
+
+    for (i, line) in text.lines().enumerate() {
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let error = |message: &str| TmError::new(format!("Line {}: {}", i + 1, message));
+        let characters = |words: &[&str]| -> Result<Vec<char>, TmError> {
+            words.iter().map(|word| {
+                let mut chars = word.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(c),
+                    _ => Err(error(&format!("Symbol {} is not a single character.", word)))
+                }
+            }).collect()
+        };
+
+        match words.as_slice() {
+            [] => {},
+            [from, read, "->", next, write, direction] => {
+                let from = state(from, &mut names);
+                state(next, &mut names);
+                let read = characters(&[read])?[0];
+                let write = characters(&[write])?[0];
+                let direction = parse_direction(direction).ok_or_else(|| error(&format!("Invalid move {}.", direction)))?;
+                rules.push((i + 1, from, read, next.to_string(), write, direction));
+            },
+            ["tape", symbols @ ..] => tape_symbols = Some(characters(symbols)?),
+            ["language", symbols @ ..] => language_symbols = Some(characters(symbols)?),
+            ["states", declared @ ..] => {
+                if !names.is_empty() {
+                    return Err(error("States must be declared before they are used."));
+                }
+                for name in declared {
+                    state(name, &mut names);
+                }
+            },
+            [kind @ ("start" | "accept" | "reject"), name] => {
+                let index = ["start", "accept", "reject"].iter().position(|k| k == kind).unwrap();
+                special[index] = Some(state(name, &mut names));
+            },
+            _ => return Err(error(&format!("Cannot understand {}.", line.trim())))
+        }
+    }
+
+    let tape_symbols = tape_symbols.ok_or_else(|| TmError::new("The tape symbols are missing.".to_string()))?;
+    let language_symbols = language_symbols.ok_or_else(|| TmError::new("The language symbols are missing.".to_string()))?;
+    let [start, accept, reject] = special;
+    let start = start.ok_or_else(|| TmError::new("The start state is missing.".to_string()))?;
+    let accept = accept.ok_or_else(|| TmError::new("The accept state is missing.".to_string()))?;
+    let reject = reject.ok_or_else(|| TmError::new("The reject state is missing.".to_string()))?;
+
+    let symbols: Vec<char> = tape_symbols.iter().chain(language_symbols.iter()).copied().collect();
+    let symbol = |c: char, line: usize| symbols.iter().position(|s| *s == c)
+        .ok_or_else(|| TmError::new(format!("Line {}: Symbol {} is not a tape or language symbol.", line, c)));
+
+    let mut transitions: Vec<Vec<Transition>> = (0..names.len()).map(|state| if state == accept || state == reject {
+        vec![]
+    } else {
+        (0..symbols.len()).map(|read| Transition::new_transition(reject, read, 0)).collect()
+    }).collect();
+    let mut defined = vec![vec![false; symbols.len()]; names.len()];
+
+    for (line, from, read, next, write, direction) in rules {
+        if from == accept || from == reject {
+            return Err(TmError::new(format!("Line {}: The halting state {} cannot have rules.", line, names[from])));
+        }
+
+        let read = symbol(read, line)?;
+        if defined[from][read] {
+            return Err(TmError::new(format!("Line {}: State {} already has a rule for {}.", line, names[from], symbols[read])));
+        }
+        defined[from][read] = true;
+        transitions[from][read] = Transition::new_transition(names.iter().position(|name| *name == next).unwrap(), symbol(write, line)?, direction);
+    }
+
+    let machine = TuringMachine::new(
+        names.into_iter().map(State::new_state).collect(),
+        language_symbols.into_iter().map(Symbol::new_symbol).collect(),
+        tape_symbols.into_iter().map(Symbol::new_symbol).collect(),
+        transitions,
+        start, accept, reject
+    );
+    machine.validate()?;
+
+    Ok(machine)
+}
+
+/// Write a machine as a machine file, leaving out the rules that reject without moving
+pub fn to_text(machine: &TuringMachine) -> Result<String, TmError> {
+    if let Some(state) = machine.view_states().iter().find(|state| state.as_str().is_empty() || state.as_str().contains(char::is_whitespace)) {
+        return Err(TmError::new(format!("State name {:?} cannot be written to a machine file.", state.as_str())));
+    }
+
+    let states = machine.view_states();
+    if let Some((i, state)) = states.iter().enumerate().find(|(i, state)| states[..*i].contains(state)) {
+        return Err(TmError::new(format!("State name {} is used by states {} and {}.", state, states.iter().position(|s| s == state).unwrap(), i)));
+    }
+
+    let symbols = machine.symbol_characters();
+    if let Some(symbol) = symbols.iter().find(|symbol| symbol.is_whitespace()) {
+        return Err(TmError::new(format!("Symbol {:?} cannot be written to a machine file.", symbol)));
+    }
+
+    let characters = |symbols: &[Symbol]| symbols.iter().map(|symbol| symbol.as_char().to_string()).collect::<Vec<String>>().join(" ");
+    let names: Vec<&str> = machine.view_states().iter().map(|state| state.as_str()).collect();
+    let reject = machine.view_reject_state();
+
+    let mut text = format!("tape {}\nlanguage {}\nstates {}\n", characters(machine.view_tape_symbols()), characters(machine.view_language_symbols()), names.join(" "));
+    text.push_str(&format!("start {}\naccept {}\nreject {}\n", names[machine.view_start_state()], names[machine.view_accept_state()], names[reject]));
+
+    for (state, row) in machine.view_transitions().iter().enumerate() {
+        if state == machine.view_accept_state() || state == reject {
+            continue;
+        }
+
+        for (read, transition) in row.iter().enumerate() {
+            if transition.next_state_id() == reject && transition.write_symbol_id() == read && transition.direction() == 0 {
+                continue;
+            }
+
+            let direction = match transition.direction() {
+                -1 => "L".to_string(),
+                1 => "R".to_string(),
+                _ => transition.direction_to_string()
+            };
+            text.push_str(&format!("{} {} -> {} {} {}\n", names[state], symbols[read], names[transition.next_state_id()], symbols[transition.write_symbol_id()], direction));
+        }
+    }
+
+    Ok(text)
+}
+
+/// Read a move written as `S`, `L`, `R`, or `L`/`R` followed by a number of cells
+fn parse_direction(text: &str) -> Option<i32> {
+    let (sign, cells) = match text.split_at_checked(1)? {
+        ("S", "") => return Some(0),
+        ("L", cells) => (-1, cells),
+        ("R", cells) => (1, cells),
+        _ => return None
+    };
+
+    if cells.is_empty() {
+        Some(sign)
+    } else {
+        cells.parse::<i32>().ok().filter(|cells| *cells > 0).map(|cells| sign * cells)
+    }
+}
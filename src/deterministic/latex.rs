@@ -0,0 +1,134 @@
+//! # LaTeX Export
+//!
+//! Typeset machines and computations for lecture notes:
+//!
+//! * [`to_tikz`] - A state diagram, needing `\usetikzlibrary{automata}`
+//! * [`to_table`] - A transition table as a `tabular`
+//! * [`trace`] - The configurations of a run in Sipser's notation `u q v`, where the head is on the
+//!   first symbol of `v`
+//!
+//! Every symbol and state name is set in text mode, with the characters LaTeX treats specially
+//! (such as the blank `_` and the start marker `>`) escaped.
+
+use crate::tape::Tape;
+use crate::util::{ ComputationStatus, Transition };
+use super::computation::Computation;
+use super::dot::edges;
+use super::machine::TuringMachine;
+
+/// Escape text so that LaTeX typesets it literally
+pub fn escape(text: &str) -> String {
+    text.chars().map(|c| match c {
+        '\\' => "\\textbackslash{}".to_string(),
+        '^' => "\\textasciicircum{}".to_string(),
+        '~' => "\\textasciitilde{}".to_string(),
+        '<' => "\\textless{}".to_string(),
+        '>' => "\\textgreater{}".to_string(),
+        '|' => "\\textbar{}".to_string(),
+        '_' | '{' | '}' | '$' | '&' | '#' | '%' => format!("\\{c}"),
+        _ => c.to_string()
+    }).collect()
+}
+
+/// The escaped characters of every symbol of a machine, tape symbols first
+fn symbols(machine: &TuringMachine) -> Vec<String> {
+    machine.symbol_characters().iter().map(|c| escape(&c.to_string())).collect()
+}
+
+/// Draw the state diagram as a `tikzpicture`, laying the states out on a grid in order
+///
+/// Transitions between the same pair of states share one edge, labelled with a line per transition.
+/// With `hide_reject` the reject state and every transition into it are left out.
+pub fn to_tikz(machine: &TuringMachine, hide_reject: bool) -> String {
+    let symbols = symbols(machine);
+    let (accept, reject) = (machine.view_accept_state(), machine.view_reject_state());
+    let shown: Vec<usize> = (0..machine.view_states().len()).filter(|state| !hide_reject || *state != reject).collect();
+    let columns = (shown.len() as f64).sqrt().ceil().max(1.0) as usize;
+
+    let mut tikz = String::from("\\begin{tikzpicture}[->, >=stealth, auto, semithick]\n");
+    for (i, state) in shown.iter().enumerate() {
+        let mut style = vec!["state"];
+        if *state == machine.view_start_state() {
+            style.push("initial");
+        }
+        if *state == accept {
+            style.push("accepting");
+        }
+        if *state == reject {
+            style.push("fill=gray!20");
+        }
+
+        let name = escape(machine.view_states()[*state].as_str());
+        tikz.push_str(&format!("  \\node[{}] (q{}) at ({}, {}) {{\\textit{{{}}}}};\n", style.join(", "), state, 3 * (i % columns), -3 * (i / columns) as i64, name));
+    }
+
+    let label = |read: usize, transition: &Transition| {
+        format!("\\texttt{{{}}}$\\rightarrow$\\texttt{{{}}},{}", symbols[read], symbols[transition.write_symbol_id()], transition.direction_to_string())
+    };
+    for (state, next, labels) in edges(machine, hide_reject, label) {
+        let shape = if next == state { "edge[loop above]" } else { "edge" };
+        tikz.push_str(&format!("  \\path (q{}) {} node[align=center] {{{}}} (q{});\n", state, shape, labels.join(" \\\\ "), next));
+    }
+
+    tikz.push_str("\\end{tikzpicture}\n");
+    tikz
+}
+
+/// Tabulate the transitions with a row per state that does not halt and a column per symbol, each
+/// entry giving the next state, the symbol written and the move
+pub fn to_table(machine: &TuringMachine) -> String {
+    let symbols = symbols(machine);
+    let (accept, reject) = (machine.view_accept_state(), machine.view_reject_state());
+
+    let mut table = format!("\\begin{{tabular}}{{l|{}}}\n", "l".repeat(symbols.len()));
+    let header: Vec<String> = symbols.iter().map(|symbol| format!("\\texttt{{{}}}", symbol)).collect();
+    table.push_str(&format!("  & {} \\\\\n  \\hline\n", header.join(" & ")));
+
+    for (state, row) in machine.view_transitions().iter().enumerate() {
+        if state == accept || state == reject {
+            continue;
+        }
+
+        let entries: Vec<String> = row.iter().map(|transition| format!(
+            "\\textit{{{}}}, \\texttt{{{}}}, {}",
+            escape(machine.view_states()[transition.next_state_id()].as_str()),
+            symbols[transition.write_symbol_id()],
+            transition.direction_to_string()
+        )).collect();
+        table.push_str(&format!("  \\textit{{{}}} & {} \\\\\n", escape(machine.view_states()[state].as_str()), entries.join(" & ")));
+    }
+
+    table.push_str("\\end{tabular}\n");
+    table
+}
+
+/// The current configuration `u q v` of a computation, with blanks after the last written cell
+/// trimmed unless the head is on them
+pub fn configuration<T: Tape>(computation: &Computation<'_, T>) -> String {
+    let machine = computation.view_machine();
+    let symbols = symbols(machine);
+    let tape = computation.view_tape();
+    let head = tape.head();
+    let end = computation.view_extent();
+
+    let cells = |range: std::ops::Range<usize>| range.map(|position| symbols[tape.get(position)].clone()).collect::<String>();
+    format!(
+        "\\texttt{{{}}}\\,\\textit{{{}}}\\,\\texttt{{{}}}",
+        cells(0..head),
+        escape(machine.view_states()[computation.view_current_state()].as_str()),
+        cells(head..end)
+    )
+}
+
+/// Run a computation until it halts, setting every configuration on its own line of a `tabular`
+pub fn trace<T: Tape>(computation: &mut Computation<'_, T>) -> String {
+    let mut lines = vec![configuration(computation)];
+    while computation.view_status() == &ComputationStatus::Executing {
+        computation.step();
+        if computation.view_status() != &ComputationStatus::Timeout {
+            lines.push(configuration(computation));
+        }
+    }
+
+    format!("\\begin{{tabular}}{{l}}\n  {}\n\\end{{tabular}}\n", lines.join(" \\\\\n  "))
+}
@@ -0,0 +1,138 @@
+//! # Linear Bounded Automata
+//!
+//! A computation mode that confines the head to the input. The tape is the start marker, the input
+//! and a single blank acting as the right end marker, and never grows. Moving left off the start
+//! marker leaves the head where it is, as on an ordinary tape, while moving right past the end
+//! marker is handled according to a [`RightEdge`] policy.
+//!
+//! An LBA has finitely many configurations, so a computation that does not halt must eventually
+//! repeat one. Repeats are detected exactly with Brent's cycle detection, which keeps a single saved
+//! configuration, and a looping computation rejects.
+
+use crate::errors::TmError;
+use crate::util::{ ComputationStatus, ComputationClock };
+use super::computation::encode_word;
+use super::machine::TuringMachine;
+
+/// What happens when the head tries to move past the right end marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RightEdge {
+    /// The computation rejects
+    Reject,
+    /// The step fails with an error, leaving the computation in its last configuration
+    Error
+}
+
+pub struct LbaComputation<'a> {
+    machine: &'a TuringMachine,
+    current_state: usize,
+    cells: Vec<usize>,
+    head: usize,
+    right_edge: RightEdge,
+    status: ComputationStatus,
+    clock: ComputationClock,
+    looped: bool,
+    saved: (usize, usize, Vec<usize>),
+    power: usize,
+    length: usize
+}
+
+impl LbaComputation<'_> {
+    pub fn view_machine(&self) -> &TuringMachine { self.machine }
+    pub fn view_current_state(&self) -> usize { self.current_state }
+    pub fn view_head_position(&self) -> usize { self.head }
+    pub fn view_tape(&self) -> &Vec<usize> { &self.cells }
+    pub fn view_status(&self) -> &ComputationStatus { &self.status }
+    pub fn view_clock(&self) -> &ComputationClock { &self.clock }
+
+    /// Whether the computation rejected because it repeated a configuration
+    pub fn view_looped(&self) -> bool { self.looped }
+}
+
+impl<'a> LbaComputation<'a> {
+    /// Create a computation from a machine and word, confined to the cells of the word
+    pub fn start(machine: &'a TuringMachine, word: &str, right_edge: RightEdge, max_time: Option<usize>) -> Result<Self, TmError> {
+        let mut cells = encode_word(machine, word)?;
+        cells.push(0);
+
+        Ok(LbaComputation {
+            machine,
+            current_state: machine.view_start_state(),
+            saved: (machine.view_start_state(), 0, cells.clone()),
+            clock: ComputationClock::clock(max_time, None, cells.len()),
+            cells,
+            head: 0,
+            right_edge,
+            status: ComputationStatus::Executing,
+            looped: false,
+            power: 1,
+            length: 0
+        })
+    }
+
+    /// Execute the next transition from the current configuration
+    pub fn step(&mut self) -> Result<(), TmError> {
+        if self.status != ComputationStatus::Executing {
+            return Ok(());
+        }
+
+        let transition = &self.machine.view_transitions()[self.current_state][self.cells[self.head]];
+        let target = self.head as i64 + transition.direction() as i64;
+        let past_edge = target >= self.cells.len() as i64;
+        if past_edge && self.right_edge == RightEdge::Error {
+            return Err(TmError::new(
+                format!("Head moved past the right end marker from cell {} at time {}.", self.head, self.clock.time)
+            ));
+        }
+
+        if let Some(status) = self.clock.tick() {
+            self.status = status;
+            return Ok(());
+        }
+
+        if past_edge {
+            self.status = ComputationStatus::Reject;
+            return Ok(());
+        }
+
+        self.current_state = transition.next_state_id();
+        self.cells[self.head] = transition.write_symbol_id();
+        self.head = target.max(0) as usize;
+
+        if self.current_state == self.machine.view_accept_state() {
+            self.status = ComputationStatus::Accept;
+        } else if self.current_state == self.machine.view_reject_state() {
+            self.status = ComputationStatus::Reject;
+        } else if self.repeats() {
+            self.looped = true;
+            self.status = ComputationStatus::Reject;
+        }
+
+        Ok(())
+    }
+
+    /// Run the computation until it halts or the head leaves the tape under [`RightEdge::Error`]
+    pub fn run(&mut self) -> Result<(), TmError> {
+        while self.status == ComputationStatus::Executing {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Compare against the saved configuration, saving the current one at every power of two steps
+    fn repeats(&mut self) -> bool {
+        let (state, head, cells) = &self.saved;
+        if *state == self.current_state && *head == self.head && *cells == self.cells {
+            return true;
+        }
+
+        self.length += 1;
+        if self.length == self.power {
+            self.saved = (self.current_state, self.head, self.cells.clone());
+            self.power *= 2;
+            self.length = 0;
+        }
+
+        false
+    }
+}
@@ -0,0 +1,234 @@
+//! # Component Library
+//!
+//! Reusable machines over a caller supplied language, meant to be combined with
+//! [`compose`](super::compose) into larger machines.
+//!
+//! Every component uses `_` as the blank and `>` as the start marker. Unless stated otherwise a
+//! component starts with the head on the start marker, works on the block of symbols between the
+//! start marker and the first blank, and accepts with the head back on the start marker. Components
+//! that need to mark cells use characters from the Unicode private use area, and always clear their
+//! marks before halting.
+
+use crate::errors::TmError;
+use super::builder::Builder;
+use super::machine::TuringMachine;
+
+const BLANK: char = '_';
+const START: char = '>';
+
+/// Move right to the first blank from anywhere on the tape, accepting with the head on the blank
+pub fn move_to_right_end(language: &[char]) -> Result<TuringMachine, TmError> {
+    check_language(language)?;
+    let mut b = Builder::new(&[BLANK, START], language);
+
+    let everything = b.symbols_except("_");
+    b.rule("start", &everything, None, 1, "start");
+    b.rule("start", "_", None, 0, "accept");
+
+    Ok(b.build())
+}
+
+/// Move left to the start marker from anywhere on the tape, accepting with the head on the start marker
+pub fn move_to_start(language: &[char]) -> Result<TuringMachine, TmError> {
+    check_language(language)?;
+    let mut b = Builder::new(&[BLANK, START], language);
+
+    let everything = b.symbols_except(">");
+    b.rule("start", &everything, None, -1, "start");
+    b.rule("start", ">", None, 0, "accept");
+
+    Ok(b.build())
+}
+
+/// Shift the input one cell to the right, leaving a blank right after the start marker
+pub fn shift_right(language: &[char]) -> Result<TuringMachine, TmError> {
+    check_language(language)?;
+    let mut b = Builder::new(&[BLANK, START], language);
+
+    b.rule("start", ">", None, 1, "carry__");
+    for carried in std::iter::once(BLANK).chain(language.iter().copied()) {
+        let carry = format!("carry_{carried}");
+        for read in language {
+            b.rule(&carry, &read.to_string(), Some(carried), 1, &format!("carry_{read}"));
+        }
+        b.rule(&carry, "_", Some(carried), -1, "return");
+    }
+    add_return(&mut b, "return", "accept");
+
+    Ok(b.build())
+}
+
+/// Append a copy of the input after a separator, turning `w` into `w separator w`
+///
+/// The separator may be one of the language symbols, in which case it must not appear in the input.
+pub fn copy_block(language: &[char], separator: char) -> Result<TuringMachine, TmError> {
+    let block = block_symbols(language, separator)?;
+    let mark = fresh_symbols(language, separator, 1)[0];
+    let mut b = Builder::new(&tape_symbols(language, separator, &[mark]), language);
+
+    let block_string: String = block.iter().collect();
+    let with_separator = format!("{block_string}{separator}");
+
+    b.rule("start", ">", None, 1, "to_end");
+    b.rule("to_end", &block_string, None, 1, "to_end");
+    b.rule("to_end", "_", Some(separator), -1, "back");
+    add_return(&mut b, "back", "pick");
+    b.rule("pick", &separator.to_string(), None, -1, "finish");
+
+    for c in &block {
+        let carry = format!("carry_{c}");
+        let restore = format!("restore_{c}");
+
+        b.rule("pick", &c.to_string(), Some(mark), 1, &carry);
+        b.rule(&carry, &with_separator, None, 1, &carry);
+        b.rule(&carry, "_", Some(*c), -1, &restore);
+        b.rule(&restore, &with_separator, None, -1, &restore);
+        b.rule(&restore, &mark.to_string(), Some(*c), 1, "pick");
+    }
+    add_return(&mut b, "finish", "accept");
+
+    Ok(b.build())
+}
+
+/// Accept when the input is `u separator v` with `u` equal to `v`, and reject otherwise
+///
+/// The separator may be one of the language symbols, in which case it must appear exactly once.
+pub fn compare_blocks(language: &[char], separator: char) -> Result<TuringMachine, TmError> {
+    let block = block_symbols(language, separator)?;
+    let marks = fresh_symbols(language, separator, block.len());
+    let mut b = Builder::new(&tape_symbols(language, separator, &marks), language);
+
+    let block_string: String = block.iter().collect();
+    let mark_string: String = marks.iter().collect();
+    let unmarked = format!("{block_string}{mark_string}");
+
+    b.rule("start", ">", None, 1, "pick");
+    b.rule("pick", &mark_string, None, 1, "pick");
+    b.rule("pick", &separator.to_string(), None, 1, "check_rest");
+    b.rule("check_rest", &mark_string, None, 1, "check_rest");
+    b.rule("check_rest", "_", None, 0, "clean_accept");
+    b.rule("check_rest", &format!("{block_string}{separator}"), None, 0, "clean_reject");
+
+    for (c, mark) in block.iter().zip(marks.iter()) {
+        let find = format!("find_separator_{c}");
+        let matching = format!("match_{c}");
+        let others: String = block.iter().filter(|other| *other != c).chain([separator, BLANK].iter()).collect();
+
+        b.rule("pick", &c.to_string(), Some(*mark), 1, &find);
+        b.rule(&find, &unmarked, None, 1, &find);
+        b.rule(&find, &separator.to_string(), None, 1, &matching);
+        b.rule(&matching, &mark_string, None, 1, &matching);
+        b.rule(&matching, &c.to_string(), Some(*mark), -1, "back");
+        b.rule(&matching, &others, None, 0, "clean_reject");
+    }
+    add_return(&mut b, "back", "pick");
+
+    for halt in ["accept", "reject"] {
+        let clean = format!("clean_{halt}");
+        let unmark = format!("unmark_{halt}");
+        let everything = b.symbols_except("_");
+
+        b.rule(&clean, &everything, None, 1, &clean);
+        b.rule(&clean, "_", None, -1, &unmark);
+        for (c, mark) in block.iter().zip(marks.iter()) {
+            b.rule(&unmark, &mark.to_string(), Some(*c), -1, &unmark);
+        }
+        b.rule(&unmark, &format!("{block_string}{separator}_"), None, -1, &unmark);
+        b.rule(&unmark, ">", None, 0, halt);
+    }
+
+    Ok(b.build())
+}
+
+/// Add one to the binary number (most significant digit first) written as the input
+pub fn increment_binary(language: &[char], zero: char, one: char) -> Result<TuringMachine, TmError> {
+    check_language(language)?;
+    if !language.contains(&zero) || !language.contains(&one) || zero == one {
+        return Err(TmError::new(format!("Language must contain the distinct digits {} and {}.", zero, one)));
+    }
+
+    let mut b = Builder::new(&[BLANK, START], language);
+    let digits: String = [zero, one].iter().collect();
+
+    b.rule("start", ">", None, 1, "to_end");
+    b.rule("to_end", &digits, None, 1, "to_end");
+    b.rule("to_end", "_", None, -1, "carry");
+    b.rule("carry", &one.to_string(), Some(zero), -1, "carry");
+    b.rule("carry", &zero.to_string(), Some(one), -1, "return");
+    b.rule("carry", ">", None, 1, "overflow");
+
+    // Every digit is now zero, so a leading one and a trailing zero make the carried number
+    b.rule("overflow", &zero.to_string(), Some(one), 1, "append");
+    b.rule("overflow", "_", Some(one), -1, "return");
+    b.rule("append", &digits, None, 1, "append");
+    b.rule("append", "_", Some(zero), -1, "return");
+    add_return(&mut b, "return", "accept");
+
+    Ok(b.build())
+}
+
+/// Overwrite the input with blanks
+pub fn erase(language: &[char]) -> Result<TuringMachine, TmError> {
+    check_language(language)?;
+    let mut b = Builder::new(&[BLANK, START], language);
+
+    let language_string: String = language.iter().collect();
+    b.rule("start", ">", None, 1, "erase");
+    b.rule("erase", &language_string, Some(BLANK), 1, "erase");
+    b.rule("erase", "_", None, -1, "return");
+    add_return(&mut b, "return", "accept");
+
+    Ok(b.build())
+}
+
+/// Add a state `from` that moves left to the start marker and enters `to` one cell to its right,
+/// or on the start marker itself when `to` is a halting state
+fn add_return(b: &mut Builder, from: &str, to: &str) {
+    let everything = b.symbols_except(">");
+    let direction = if to == "accept" || to == "reject" { 0 } else { 1 };
+
+    b.rule(from, &everything, None, -1, from);
+    b.rule(from, ">", None, direction, to);
+}
+
+fn check_language(language: &[char]) -> Result<(), TmError> {
+    for (i, c) in language.iter().enumerate() {
+        if *c == BLANK || *c == START {
+            return Err(TmError::new(format!("Symbol {} is reserved and cannot be in the language.", c)));
+        }
+
+        if language[..i].contains(c) {
+            return Err(TmError::new(format!("Symbol {} appears more than once in the language.", c)));
+        }
+    }
+
+    Ok(())
+}
+
+/// The symbols that can make up a block, which is every language symbol except the separator
+fn block_symbols(language: &[char], separator: char) -> Result<Vec<char>, TmError> {
+    check_language(language)?;
+    if separator == BLANK || separator == START {
+        return Err(TmError::new(format!("Symbol {} cannot be used as a separator.", separator)));
+    }
+
+    Ok(language.iter().copied().filter(|c| *c != separator).collect())
+}
+
+/// The tape symbols of a component, including the separator when it is not a language symbol
+fn tape_symbols(language: &[char], separator: char, marks: &[char]) -> Vec<char> {
+    let mut symbols = vec![BLANK, START];
+    if !language.contains(&separator) {
+        symbols.push(separator);
+    }
+    symbols.extend_from_slice(marks);
+    symbols
+}
+
+/// `count` private use characters that clash with neither the language nor the separator
+fn fresh_symbols(language: &[char], separator: char, count: usize) -> Vec<char> {
+    ('\u{E000}'..='\u{F8FF}')
+        .filter(|c| !language.contains(c) && *c != separator)
+        .take(count)
+        .collect()
+}
@@ -13,8 +13,11 @@
 //! * $\Gamma$ - The set of tape symbols and language symbols
 //! * $\delta$ - The set of transitions $\delta_{ij}: Q \times \Gamma \mapsto Q \times \Gamma \times {-1,1}
 
-use crate::{errors::TmError, util::{State, Symbol, Transition}};
+use crate::{errors::TmError, tape::Tape, util::{State, Symbol, Transition}};
 use super::computation::Computation;
+use super::compiled::CompiledMachine;
+use super::lba::{ LbaComputation, RightEdge };
+use super::dot::{ self, DotOptions };
 
 /// The TM 7-Tuple definition found in Sipser
 pub struct TuringMachine {
@@ -29,14 +32,39 @@ pub struct TuringMachine {
 
 impl TuringMachine {
     /// Construct an unbounded computation using the current machine and a specified word
-    pub fn compute(&self, word: &String) -> Result<Computation, TmError> {
+    pub fn compute(&self, word: &str) -> Result<Computation<'_>, TmError> {
         Computation::start(self, word)
     }
 
     /// Construct a bounded computation using the current machine and a specified word
-    pub fn bounded_compute(&self, word: &String, limits: (Option<usize>, Option<usize>)) -> Result<Computation, TmError> {
+    pub fn bounded_compute(&self, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<Computation<'_>, TmError> {
         Computation::bounded_start(self, word, limits)
     }
+
+    /// Construct a bounded computation using the current machine and a specified word, storing the tape in `T`
+    pub fn compute_on_tape<T: Tape>(&self, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<Computation<'_, T>, TmError> {
+        Computation::start_on_tape(self, word, limits)
+    }
+
+    /// Construct a computation confined to the cells of the word, as a linear bounded automaton
+    pub fn lba_compute(&self, word: &str, right_edge: RightEdge, max_time: Option<usize>) -> Result<LbaComputation<'_>, TmError> {
+        LbaComputation::start(self, word, right_edge, max_time)
+    }
+
+    /// Compile the machine into a flat transition table for faster stepping
+    pub fn compile(&self) -> Result<CompiledMachine<'_>, TmError> {
+        CompiledMachine::compile(self)
+    }
+
+    /// Render the state diagram in the Graphviz DOT language
+    pub fn to_dot(&self) -> String {
+        dot::render(self, &DotOptions::default())
+    }
+
+    /// Render the state diagram in the Graphviz DOT language, hiding or highlighting parts of it
+    pub fn to_dot_with(&self, options: &DotOptions) -> String {
+        dot::render(self, options)
+    }
 }
 
 impl TuringMachine {
@@ -46,6 +74,33 @@ impl TuringMachine {
         }
     }
 
+    /// Check that the machine is well formed: distinct symbols including a blank and start marker,
+    /// existing start, accept and reject states, and a complete row of valid transitions for every
+    /// state that does not halt
+    pub fn validate(&self) -> Result<(), TmError> {
+        check_definition(self.states.len(), &self.tape_symbols, &self.language_symbols, [self.start_state, self.accept_state, self.reject_state])?;
+
+        let symbols = self.tape_symbols.len() + self.language_symbols.len();
+        for (state, name) in self.states.iter().enumerate() {
+            if state == self.accept_state || state == self.reject_state {
+                continue;
+            }
+
+            let row = self.transitions.get(state).map_or(&[][..], |row| &row[..]);
+            if row.len() != symbols {
+                return Err(TmError::new(format!("State {} has {} transitions for {} symbols.", name, row.len(), symbols)));
+            }
+
+            for transition in row {
+                if transition.next_state_id() >= self.states.len() || transition.write_symbol_id() >= symbols {
+                    return Err(TmError::new(format!("State {} has a transition to a missing state or symbol.", name)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn view_states(&self) -> &Vec<State> { &self.states }
     pub fn view_tape_symbols(&self) -> &Vec<Symbol> { &self.tape_symbols }
     pub fn view_language_symbols(&self) -> &Vec<Symbol> { &self.language_symbols }
@@ -53,6 +108,31 @@ impl TuringMachine {
     pub fn view_start_state(&self) -> usize { self.start_state }
     pub fn view_accept_state(&self) -> usize { self.accept_state }
     pub fn view_reject_state(&self) -> usize { self.reject_state }
+
+    /// The character of every symbol indexed by its id, tape symbols first
+    pub fn symbol_characters(&self) -> Vec<char> {
+        self.tape_symbols.iter().chain(self.language_symbols.iter()).map(|symbol| symbol.as_char()).collect()
+    }
+}
+
+/// Check the parts of a machine definition shared by every kind of machine on a one-way tape
+pub(crate) fn check_definition(states: usize, tape_symbols: &[Symbol], language_symbols: &[Symbol], special_states: [usize; 3]) -> Result<(), TmError> {
+    if tape_symbols.len() < 2 {
+        return Err(TmError::new("Machine needs a blank and a start marker as its first tape symbols.".to_string()));
+    }
+
+    let symbols: Vec<&Symbol> = tape_symbols.iter().chain(language_symbols.iter()).collect();
+    for (i, symbol) in symbols.iter().enumerate() {
+        if symbols[..i].contains(symbol) {
+            return Err(TmError::new(format!("Symbol {} appears more than once.", symbol)));
+        }
+    }
+
+    if let Some(state) = special_states.iter().find(|state| **state >= states) {
+        return Err(TmError::new(format!("State {} does not exist.", state)));
+    }
+
+    Ok(())
 }
 
 impl Default for TuringMachine {
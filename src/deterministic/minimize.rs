@@ -0,0 +1,103 @@
+//! # Minimization
+//!
+//! Shrink a machine without changing what it does. Unreachable states are removed, then states
+//! that behave the same are merged by partition refinement: starting from the accept state, the
+//! reject state, and every other state in one block, a block is split until all of its states write
+//! the same symbol, move the same way, and continue in the same block on every symbol.
+//!
+//! A transition on a symbol that can never be under the head in its state is never taken, so it is
+//! a don't-care: it is replaced by one that leaves the symbol in place and rejects.
+//!
+//! The minimized machine takes exactly the same steps as the original on every input, only with
+//! fewer names for its states.
+
+use std::collections::{ HashMap, HashSet };
+use std::collections::hash_map::Entry;
+
+use crate::errors::TmError;
+use crate::util::{ State, Symbol, Transition };
+use super::analysis::analyze;
+use super::machine::TuringMachine;
+
+/// The block a transition continues in, with the symbol it writes and its direction
+type Signature = (usize, usize, i32);
+
+/// Remove the unreachable states of a machine and merge its equivalent states
+///
+/// Each merged state keeps the name of its lowest numbered member.
+pub fn minimize(machine: &TuringMachine) -> Result<TuringMachine, TmError> {
+    machine.validate()?;
+
+    let (accept, reject) = (machine.view_accept_state(), machine.view_reject_state());
+    let report = analyze(machine);
+    let dead: HashSet<(usize, usize)> = report.dead_transitions.into_iter().collect();
+    let live = |state: usize, symbol: usize, t: &Transition| if dead.contains(&(state, symbol)) {
+        (reject, symbol, 0)
+    } else {
+        (t.next_state_id(), t.write_symbol_id(), t.direction())
+    };
+
+    let mut kept = report.reachable_states;
+    for state in [accept, reject] {
+        if !kept.contains(&state) {
+            kept.push(state);
+        }
+    }
+    kept.sort();
+
+    // Refine until the number of blocks stops growing
+    let mut block: HashMap<usize, usize> = kept.iter().map(|state| (*state, match *state {
+        state if state == accept => 0,
+        state if state == reject => 1,
+        _ => 2
+    })).collect();
+    let mut blocks = 0;
+
+    loop {
+        let mut signatures: HashMap<(usize, Vec<Signature>), usize> = HashMap::new();
+        let refined: HashMap<usize, usize> = kept.iter().map(|state| {
+            let row = if *state == accept || *state == reject { vec![] } else {
+                machine.view_transitions()[*state].iter().enumerate()
+                    .map(|(symbol, t)| live(*state, symbol, t))
+                    .map(|(next, write, direction)| (block[&next], write, direction))
+                    .collect()
+            };
+            let next = signatures.len();
+            (*state, *signatures.entry((block[state], row)).or_insert(next))
+        }).collect();
+
+        block = refined;
+        if signatures.len() == blocks {
+            break;
+        }
+        blocks = signatures.len();
+    }
+
+    // Number the blocks by their lowest member, keeping the states in their original order
+    let mut representatives: Vec<usize> = vec![];
+    let mut ids: HashMap<usize, usize> = HashMap::new();
+    for state in &kept {
+        if let Entry::Vacant(entry) = ids.entry(block[state]) {
+            entry.insert(representatives.len());
+            representatives.push(*state);
+        }
+    }
+    let id = |state: usize| ids[&block[&state]];
+
+    let transitions = representatives.iter().map(|state| if *state == accept || *state == reject {
+        vec![]
+    } else {
+        machine.view_transitions()[*state].iter().enumerate()
+            .map(|(symbol, t)| live(*state, symbol, t))
+            .map(|(next, write, direction)| Transition::new_transition(id(next), write, direction))
+            .collect()
+    }).collect();
+
+    Ok(TuringMachine::new(
+        representatives.iter().map(|state| State::new_state(machine.view_states()[*state].as_string().clone())).collect(),
+        machine.view_language_symbols().iter().map(|symbol| Symbol::new_symbol(symbol.as_char())).collect(),
+        machine.view_tape_symbols().iter().map(|symbol| Symbol::new_symbol(symbol.as_char())).collect(),
+        transitions,
+        id(machine.view_start_state()), id(accept), id(reject)
+    ))
+}
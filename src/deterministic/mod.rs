@@ -1,5 +1,29 @@
 pub mod computation;
 pub mod machine;
+pub mod compiled;
+pub mod codegen;
+pub mod universal;
+pub mod compose;
+pub mod library;
+pub mod catalogue;
+pub mod program;
+pub mod regular;
+pub mod lba;
+pub mod multihead;
+pub mod oracle;
+pub mod enumerator;
+pub mod encoding;
+pub mod transducer;
+pub mod analysis;
+pub mod minimize;
+pub mod dot;
+pub mod diagram;
+pub mod display;
+pub mod latex;
+pub mod format;
+
+pub(crate) mod builder;
 
 pub use crate::deterministic::computation::Computation as DetComputation;
-pub use crate::deterministic::machine::TuringMachine as DTM;
\ No newline at end of file
+pub use crate::deterministic::machine::TuringMachine as DTM;
+pub use crate::deterministic::compiled::CompiledMachine;
@@ -0,0 +1,376 @@
+//! # Multi-Head Turing Machines
+//!
+//! Machines with `k` heads on a single shared tape. Every head starts on the start marker, and a
+//! transition reads the `k` symbols under the heads, then writes a symbol and moves each head.
+//!
+//! When several heads write to the same cell the head with the lowest index wins, so head `0` always
+//! has its write kept.
+
+use std::collections::{ HashMap, HashSet };
+
+use crate::errors::TmError;
+use crate::util::{ ComputationStatus, ComputationClock, State, Symbol };
+use super::builder::Builder;
+use super::computation::encode_symbols;
+use super::machine::{ TuringMachine, check_definition };
+
+/// The most transitions per state [`MultiHeadMachine::from_fn`] tabulates
+const MAX_TABULATED: usize = 1 << 24;
+
+/// The number of tuples of symbols `heads` heads can read, checking that there are 1 to 8 heads
+fn tuple_count(heads: usize, symbols: usize) -> Result<usize, TmError> {
+    if heads == 0 || heads > 8 {
+        return Err(TmError::new(format!("Machine needs between 1 and 8 heads, not {}.", heads)));
+    }
+
+    symbols.checked_pow(heads as u32)
+        .ok_or_else(|| TmError::new(format!("Machine with {} heads and {} symbols has too many tuples of symbols.", heads, symbols)))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiTransition {
+    next_state: usize,
+    writes: Vec<usize>,
+    moves: Vec<i32>
+}
+
+impl MultiTransition {
+    pub fn new_transition(next_state: usize, writes: Vec<usize>, moves: Vec<i32>) -> Self {
+        MultiTransition { next_state, writes, moves }
+    }
+
+    pub fn next_state_id(&self) -> usize { self.next_state }
+    pub fn write_symbol_ids(&self) -> &Vec<usize> { &self.writes }
+    pub fn directions(&self) -> &Vec<i32> { &self.moves }
+}
+
+/// A machine with `heads` heads, where the row of a state holds a transition for every tuple of
+/// symbols the heads can read, ordered by [`MultiHeadMachine::read_index`]
+pub struct MultiHeadMachine {
+    heads: usize,
+    states: Vec<State>,
+    tape_symbols: Vec<Symbol>,
+    language_symbols: Vec<Symbol>,
+    transitions: Vec<Vec<MultiTransition>>,
+    start_state: usize,
+    accept_state: usize,
+    reject_state: usize
+}
+
+impl MultiHeadMachine {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(heads: usize, states: Vec<State>, language_symbols: Vec<Symbol>, tape_symbols: Vec<Symbol>, transitions: Vec<Vec<MultiTransition>>, start_state: usize, accept_state: usize, reject_state: usize) -> Result<Self, TmError> {
+        check_definition(states.len(), &tape_symbols, &language_symbols, [start_state, accept_state, reject_state])?;
+
+        let symbols = tape_symbols.len() + language_symbols.len();
+        let tuples = tuple_count(heads, symbols)?;
+        for (state, name) in states.iter().enumerate() {
+            if state == accept_state || state == reject_state {
+                continue;
+            }
+
+            let row = transitions.get(state).map_or(&[][..], |row| &row[..]);
+            if row.len() != tuples {
+                return Err(TmError::new(format!("State {} has {} transitions for {} tuples of symbols.", name, row.len(), tuples)));
+            }
+
+            for transition in row {
+                if transition.next_state >= states.len() || transition.writes.iter().any(|write| *write >= symbols) {
+                    return Err(TmError::new(format!("State {} has a transition to a missing state or symbol.", name)));
+                }
+
+                if transition.writes.len() != heads || transition.moves.len() != heads {
+                    return Err(TmError::new(format!("State {} has a transition without a write and move for every head.", name)));
+                }
+            }
+        }
+
+        Ok(Self { heads, states, tape_symbols, language_symbols, transitions, start_state, accept_state, reject_state })
+    }
+
+    /// Tabulate the transitions of every non-halting state from a function of the state and the symbols read
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_fn(heads: usize, states: Vec<State>, language_symbols: Vec<Symbol>, tape_symbols: Vec<Symbol>, start_state: usize, accept_state: usize, reject_state: usize, transition: impl Fn(usize, &[usize]) -> MultiTransition) -> Result<Self, TmError> {
+        let symbols = tape_symbols.len() + language_symbols.len();
+        let tuples = tuple_count(heads, symbols)?;
+        if tuples > MAX_TABULATED {
+            return Err(TmError::new(format!("Tabulating {} tuples of symbols per state is too many, at most {} are allowed.", tuples, MAX_TABULATED)));
+        }
+
+        let transitions = (0..states.len()).map(|state| {
+            if state == accept_state || state == reject_state {
+                return vec![];
+            }
+
+            (0..tuples).map(|index| {
+                let reads: Vec<usize> = (0..heads).map(|head| index / symbols.pow(head as u32) % symbols).collect();
+                transition(state, &reads)
+            }).collect()
+        }).collect();
+
+        Self::new(heads, states, language_symbols, tape_symbols, transitions, start_state, accept_state, reject_state)
+    }
+
+    /// Construct a bounded computation using the current machine and a specified word
+    pub fn compute(&self, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<MultiHeadComputation<'_>, TmError> {
+        MultiHeadComputation::start(self, word, limits)
+    }
+
+    /// The position in a row of the transition for the symbols read by each head, with head `0` least significant
+    pub fn read_index(&self, reads: &[usize]) -> usize {
+        let symbols = self.tape_symbols.len() + self.language_symbols.len();
+        reads.iter().rev().fold(0, |index, read| index * symbols + read)
+    }
+
+    pub fn view_heads(&self) -> usize { self.heads }
+    pub fn view_states(&self) -> &Vec<State> { &self.states }
+    pub fn view_tape_symbols(&self) -> &Vec<Symbol> { &self.tape_symbols }
+    pub fn view_language_symbols(&self) -> &Vec<Symbol> { &self.language_symbols }
+    pub fn view_transitions(&self) -> &Vec<Vec<MultiTransition>> { &self.transitions }
+    pub fn view_start_state(&self) -> usize { self.start_state }
+    pub fn view_accept_state(&self) -> usize { self.accept_state }
+    pub fn view_reject_state(&self) -> usize { self.reject_state }
+}
+
+pub struct MultiHeadComputation<'a> {
+    machine: &'a MultiHeadMachine,
+    current_state: usize,
+    cells: Vec<usize>,
+    heads: Vec<usize>,
+    status: ComputationStatus,
+    clock: ComputationClock
+}
+
+impl MultiHeadComputation<'_> {
+    pub fn view_machine(&self) -> &MultiHeadMachine { self.machine }
+    pub fn view_current_state(&self) -> usize { self.current_state }
+    pub fn view_head_positions(&self) -> &Vec<usize> { &self.heads }
+    pub fn view_tape(&self) -> &Vec<usize> { &self.cells }
+    pub fn view_status(&self) -> &ComputationStatus { &self.status }
+    pub fn view_clock(&self) -> &ComputationClock { &self.clock }
+}
+
+impl<'a> MultiHeadComputation<'a> {
+    /// Create a bounded computation from a machine and word with every head on the start marker
+    pub fn start(machine: &'a MultiHeadMachine, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<Self, TmError> {
+        let cells = encode_symbols(&machine.tape_symbols, &machine.language_symbols, word)?;
+
+        Ok(MultiHeadComputation {
+            machine,
+            current_state: machine.start_state,
+            clock: ComputationClock::clock(limits.0, limits.1, cells.len()),
+            cells,
+            heads: vec![0; machine.heads],
+            status: ComputationStatus::Executing
+        })
+    }
+
+    /// Execute the next transition from the current configuration
+    pub fn step(&mut self) {
+        if self.status != ComputationStatus::Executing {
+            return;
+        }
+
+        if let Some(status) = self.clock.tick() {
+            self.status = status;
+            return;
+        }
+
+        let reads: Vec<usize> = self.heads.iter().map(|head| self.cells[*head]).collect();
+        let transition = &self.machine.transitions[self.current_state][self.machine.read_index(&reads)];
+
+        // Write from the last head to the first so that lower heads win conflicts
+        for (head, write) in self.heads.iter().zip(transition.writes.iter()).rev() {
+            self.cells[*head] = *write;
+        }
+
+        self.current_state = transition.next_state;
+        let length = self.cells.len();
+        for (head, direction) in self.heads.iter_mut().zip(transition.moves.iter()) {
+            *head = (*head as i64 + *direction as i64).max(0) as usize;
+            if *head >= self.cells.len() {
+                self.cells.resize(*head + 1, 0);
+            }
+        }
+
+        if self.cells.len() > length {
+            if let Some(status) = self.clock.occupy(self.cells.len()) {
+                self.status = status;
+                return;
+            }
+        }
+
+        if self.current_state == self.machine.accept_state {
+            self.status = ComputationStatus::Accept;
+        } else if self.current_state == self.machine.reject_state {
+            self.status = ComputationStatus::Reject;
+        }
+    }
+
+    /// Run the Computation until it halts
+    pub fn run(&mut self) {
+        while self.status == ComputationStatus::Executing {
+            self.step();
+        }
+    }
+}
+
+/// A stage of simulating one multi-head transition on a single head, where head sets are bitmasks
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Phase {
+    /// Return to the start marker before reading
+    Rewind(usize),
+    /// Sweep right collecting the symbol under every head
+    Read(usize, Vec<Option<usize>>),
+    /// Sweep left writing, moving heads left, and noting heads still to move right
+    Write { state: usize, index: usize, remaining: u32, carry: u32, pending: u32 },
+    /// Return to the start marker before moving heads right
+    RewindMove { state: usize, index: usize, pending: u32 },
+    /// Sweep right moving heads right
+    Move { state: usize, index: usize, pending: u32 },
+    /// Place heads moved right on the cell under the head
+    Deposit { state: usize, index: usize, pending: u32, carry: u32 }
+}
+
+impl MultiHeadMachine {
+    /// Convert the machine into a single head machine that marks the heads on each cell
+    ///
+    /// Every tape cell holds a symbol together with the set of heads on it. Each step rewinds to the
+    /// start marker, sweeps right until every head has been read, sweeps back left writing and moving
+    /// heads left, then sweeps right again to move heads right. Heads may only move one cell at a
+    /// time, and the start marker must stay in the first cell.
+    pub fn to_single_head(&self) -> Result<TuringMachine, TmError> {
+        let symbols: Vec<char> = self.tape_symbols.iter().chain(self.language_symbols.iter()).map(|symbol| symbol.as_char()).collect();
+        if self.transitions.iter().flatten().flat_map(|t| t.moves.iter()).any(|direction| direction.abs() > 1) {
+            return Err(TmError::new("Heads may only move one cell at a time when converting to a single head.".to_string()));
+        }
+
+        let masks = 1u32 << self.heads;
+        let all = masks - 1;
+        let mut fresh = ('\u{E000}'..='\u{F8FF}').filter(|c| !symbols.contains(c));
+        let marked: Vec<Vec<char>> = symbols.iter().map(|_| fresh.by_ref().take(masks as usize - 1).collect()).collect();
+        if marked.last().is_some_and(|chars| chars.len() + 1 < masks as usize) {
+            return Err(TmError::new("Too many symbols and heads to mark every combination.".to_string()));
+        }
+        let cell = |symbol: usize, mask: u32| if mask == 0 { symbols[symbol] } else { marked[symbol][mask as usize - 1] };
+
+        let tape_symbols: Vec<char> = self.tape_symbols.iter().map(|symbol| symbol.as_char())
+            .chain(marked.iter().flatten().copied())
+            .collect();
+        let language_symbols: Vec<char> = self.language_symbols.iter().map(|symbol| symbol.as_char()).collect();
+        let mut b = Builder::new(&tape_symbols, &language_symbols);
+
+        let mut names: HashMap<Phase, String> = HashMap::new();
+        let mut name = |phase: &Phase| names.entry(phase.clone()).or_insert_with(|| format!("{:?}", phase)).clone();
+        let finish = |next: usize| {
+            if next == self.accept_state {
+                None
+            } else if next == self.reject_state {
+                Some(false)
+            } else {
+                Some(true)
+            }
+        };
+
+        let start = Phase::Rewind(self.start_state);
+        b.rule("start", &symbols[1].to_string(), Some(cell(1, all)), 0, &name(&start));
+
+        let mut pending_phases = vec![start.clone()];
+        let mut seen = HashSet::from([start]);
+        while let Some(phase) = pending_phases.pop() {
+            let from = name(&phase);
+
+            for symbol in 0..symbols.len() {
+                for mask in 0..masks {
+                    let read = cell(symbol, mask).to_string();
+                    let (write, direction, next) = match &phase {
+                        Phase::Rewind(state) => if symbol == 1 {
+                            (None, 0, Phase::Read(*state, vec![None; self.heads]))
+                        } else {
+                            (None, -1, phase.clone())
+                        },
+                        Phase::Read(state, reads) => {
+                            let reads: Vec<Option<usize>> = reads.iter().enumerate()
+                                .map(|(head, read)| if mask & (1 << head) != 0 { Some(symbol) } else { *read })
+                                .collect();
+
+                            if reads.iter().all(|read| read.is_some()) {
+                                let reads: Vec<usize> = reads.into_iter().flatten().collect();
+                                (None, 0, Phase::Write { state: *state, index: self.read_index(&reads), remaining: all, carry: 0, pending: 0 })
+                            } else {
+                                (None, 1, Phase::Read(*state, reads))
+                            }
+                        },
+                        Phase::Write { state, index, remaining, carry, pending } => {
+                            let transition = &self.transitions[*state][*index];
+                            let here = mask & remaining;
+                            let moving = |direction: i32| (0..self.heads)
+                                .filter(|head| here & (1 << head) != 0 && transition.moves[*head] == direction)
+                                .fold(0, |set, head| set | (1 << head));
+
+                            let written = if here == 0 { symbol } else { transition.writes[here.trailing_zeros() as usize] };
+                            let mut left = moving(-1);
+                            let mut new_mask = (mask & !left) | carry;
+                            if symbol == 1 {
+                                new_mask |= left;
+                                left = 0;
+                            }
+
+                            let remaining = remaining & !here;
+                            let pending = pending | moving(1);
+                            let write = Some(cell(written, new_mask));
+
+                            if remaining == 0 && left == 0 {
+                                if pending != 0 {
+                                    (write, 0, Phase::RewindMove { state: *state, index: *index, pending })
+                                } else {
+                                    let next = transition.next_state;
+                                    match finish(next) {
+                                        Some(true) => (write, 0, Phase::Rewind(next)),
+                                        Some(false) => { b.rule(&from, &read, write, 0, "reject"); continue; },
+                                        None => { b.rule(&from, &read, write, 0, "accept"); continue; }
+                                    }
+                                }
+                            } else {
+                                (write, -1, Phase::Write { state: *state, index: *index, remaining, carry: left, pending })
+                            }
+                        },
+                        Phase::RewindMove { state, index, pending } => if symbol == 1 {
+                            (None, 0, Phase::Move { state: *state, index: *index, pending: *pending })
+                        } else {
+                            (None, -1, phase.clone())
+                        },
+                        Phase::Move { state, index, pending } => {
+                            let movers = mask & pending;
+                            if movers == 0 {
+                                (None, 1, phase.clone())
+                            } else {
+                                (Some(cell(symbol, mask & !movers)), 1, Phase::Deposit { state: *state, index: *index, pending: pending & !movers, carry: movers })
+                            }
+                        },
+                        Phase::Deposit { state, index, pending, carry } => {
+                            let write = Some(cell(symbol, mask | carry));
+                            if *pending != 0 {
+                                (write, 0, Phase::Move { state: *state, index: *index, pending: *pending })
+                            } else {
+                                let next = self.transitions[*state][*index].next_state;
+                                match finish(next) {
+                                    Some(true) => (write, 0, Phase::Rewind(next)),
+                                    Some(false) => { b.rule(&from, &read, write, 0, "reject"); continue; },
+                                    None => { b.rule(&from, &read, write, 0, "accept"); continue; }
+                                }
+                            }
+                        }
+                    };
+
+                    b.rule(&from, &read, write, direction, &name(&next));
+                    if seen.insert(next.clone()) {
+                        pending_phases.push(next);
+                    }
+                }
+            }
+        }
+
+        Ok(b.build())
+    }
+}
@@ -0,0 +1,156 @@
+//! # Oracle Turing Machines
+//!
+//! A deterministic machine with a write-only query tape and an oracle. Alongside its ordinary
+//! transition, every `(state, symbol)` pair carries a [`QueryAction`] on the query tape. Entering the
+//! query state asks the oracle whether the query string is in its language, and the machine
+//! continues in the yes or no state with an empty query tape, all within the same step.
+//!
+//! Stepping on the main tape is done by the ordinary [`Computation`].
+
+use crate::errors::TmError;
+use crate::tape::{ Tape, DenseTape };
+use crate::util::{ ComputationStatus, ComputationClock };
+use super::computation::Computation;
+use super::machine::TuringMachine;
+
+/// Decides membership of query strings
+pub trait Oracle {
+    fn decide(&self, query: &str) -> bool;
+}
+
+impl<F: Fn(&str) -> bool> Oracle for F {
+    fn decide(&self, query: &str) -> bool { self(query) }
+}
+
+/// What a transition does to the query tape
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryAction {
+    Nothing,
+    /// Append the symbol with this id to the query
+    Append(usize),
+    /// Erase the whole query
+    Clear
+}
+
+pub struct OracleMachine {
+    machine: TuringMachine,
+    query_actions: Vec<Vec<QueryAction>>,
+    query_state: usize,
+    yes_state: usize,
+    no_state: usize
+}
+
+impl OracleMachine {
+    /// Create an oracle machine, where `query_actions[q][s]` is performed alongside the transition of
+    /// `machine` from state `q` on symbol `s`, and a missing row does nothing to the query tape
+    pub fn new(machine: TuringMachine, query_actions: Vec<Vec<QueryAction>>, query_state: usize, yes_state: usize, no_state: usize) -> Result<Self, TmError> {
+        let states = machine.view_states().len();
+        let symbols = machine.view_tape_symbols().len() + machine.view_language_symbols().len();
+
+        if let Some(state) = [query_state, yes_state, no_state].iter().find(|state| **state >= states) {
+            return Err(TmError::new(format!("State {} does not exist.", state)));
+        }
+
+        if query_actions.len() > states {
+            return Err(TmError::new(format!("There are query actions for {} states but only {} states.", query_actions.len(), states)));
+        }
+
+        for (state, row) in query_actions.iter().enumerate() {
+            if !row.is_empty() && row.len() != symbols {
+                return Err(TmError::new(format!("State {} has {} query actions for {} symbols.", state, row.len(), symbols)));
+            }
+
+            if row.iter().any(|action| matches!(action, QueryAction::Append(symbol) if *symbol >= symbols)) {
+                return Err(TmError::new(format!("State {} appends a missing symbol to the query.", state)));
+            }
+        }
+
+        Ok(Self { machine, query_actions, query_state, yes_state, no_state })
+    }
+
+    /// Construct a bounded computation using the current machine, an oracle and a specified word
+    pub fn compute<'a>(&'a self, oracle: &'a dyn Oracle, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<OracleComputation<'a>, TmError> {
+        OracleComputation::start(self, oracle, word, limits)
+    }
+
+    pub fn view_machine(&self) -> &TuringMachine { &self.machine }
+    pub fn view_query_actions(&self) -> &Vec<Vec<QueryAction>> { &self.query_actions }
+    pub fn view_query_state(&self) -> usize { self.query_state }
+    pub fn view_yes_state(&self) -> usize { self.yes_state }
+    pub fn view_no_state(&self) -> usize { self.no_state }
+}
+
+pub struct OracleComputation<'a> {
+    machine: &'a OracleMachine,
+    oracle: &'a dyn Oracle,
+    computation: Computation<'a, DenseTape>,
+    query: Vec<usize>,
+    queries: Vec<(String, bool)>
+}
+
+impl OracleComputation<'_> {
+    pub fn view_machine(&self) -> &OracleMachine { self.machine }
+    pub fn view_computation(&self) -> &Computation<'_, DenseTape> { &self.computation }
+    pub fn view_current_state(&self) -> usize { self.computation.view_current_state() }
+    pub fn view_status(&self) -> &ComputationStatus { self.computation.view_status() }
+    pub fn view_clock(&self) -> &ComputationClock { self.computation.view_clock() }
+
+    /// The query tape as it currently stands
+    pub fn view_query(&self) -> String { self.decode(&self.query) }
+
+    /// Every query asked so far with the oracle's answer
+    pub fn view_queries(&self) -> &Vec<(String, bool)> { &self.queries }
+
+    fn decode(&self, symbols: &[usize]) -> String {
+        let characters = self.machine.machine.symbol_characters();
+        symbols.iter().map(|id| characters[*id]).collect()
+    }
+}
+
+impl<'a> OracleComputation<'a> {
+    /// Create a bounded computation with an empty query tape
+    pub fn start(machine: &'a OracleMachine, oracle: &'a dyn Oracle, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<Self, TmError> {
+        Ok(OracleComputation {
+            machine,
+            oracle,
+            computation: machine.machine.bounded_compute(word, limits)?,
+            query: vec![],
+            queries: vec![]
+        })
+    }
+
+    /// Execute the next transition, asking the oracle if it enters the query state
+    pub fn step(&mut self) {
+        if self.computation.view_status() != &ComputationStatus::Executing {
+            return;
+        }
+
+        let state = self.computation.view_current_state();
+        let symbol = self.computation.view_tape().read();
+        self.computation.step();
+        if self.computation.view_status() == &ComputationStatus::Timeout {
+            return;
+        }
+
+        match self.machine.query_actions.get(state).and_then(|row| row.get(symbol)) {
+            Some(QueryAction::Append(symbol)) => self.query.push(*symbol),
+            Some(QueryAction::Clear) => self.query.clear(),
+            Some(QueryAction::Nothing) | None => {}
+        }
+
+        if self.computation.view_status() == &ComputationStatus::Executing && self.computation.view_current_state() == self.machine.query_state {
+            let query = self.decode(&self.query);
+            let answer = self.oracle.decide(&query);
+            self.queries.push((query, answer));
+            self.query.clear();
+            self.computation.jump(if answer { self.machine.yes_state } else { self.machine.no_state });
+        }
+    }
+
+    /// Run the Computation until it halts
+    pub fn run(&mut self) {
+        while self.computation.view_status() == &ComputationStatus::Executing {
+            self.step();
+        }
+    }
+}
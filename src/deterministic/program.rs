@@ -0,0 +1,341 @@
+//! # Structured Programs
+//!
+//! A compiler from a tiny structured language into a [`TuringMachine`].
+//!
+//! ```text
+//! // Accept words over 0 and 1 that end in 0
+//! language 0 1
+//! move right
+//! while read 0 1
+//!     move right
+//! end
+//! move left
+//! if read 0
+//!     accept
+//! end
+//! reject
+//! ```
+//!
+//! A program starts with a `language` line listing the input symbols, optionally followed by a
+//! `tape` line listing extra tape symbols. The blank `_` and start marker `>` are always available.
+//! Every other line holds a single statement
+//!
+//! * `move left [n]` / `move right [n]` - move the head, one cell unless `n` is given
+//! * `write c` - write the symbol `c` under the head
+//! * `if read c...` / `else` / `end` - branch on the symbol under the head
+//! * `while read c...` / `end` - loop while the symbol under the head is one of the listed symbols
+//! * `name:` - label the next statement
+//! * `goto name` - continue at a label
+//! * `accept` / `reject` - halt
+//!
+//! Running off the end of the program rejects. Comments start with `//`.
+//!
+//! The machine spends its first step in a `start` state that leaves the head on the start marker.
+//! After that every statement other than `goto`, `accept` and `reject` becomes one state, named
+//! after its line, and takes one step. Jumps cost nothing: the statement before a jump transitions
+//! straight into its target.
+
+use std::collections::HashMap;
+
+use crate::errors::TmError;
+use crate::util::{ State, Symbol, Transition };
+use super::machine::TuringMachine;
+
+/// A compiled program with a map from its states back to source lines
+pub struct Program {
+    machine: TuringMachine,
+    lines: Vec<Option<usize>>,
+    source: Vec<String>
+}
+
+impl Program {
+    pub fn view_machine(&self) -> &TuringMachine { &self.machine }
+
+    /// The source line (counting from 1) of the statement each state executes
+    pub fn view_source_map(&self) -> &Vec<Option<usize>> { &self.lines }
+
+    /// The source line (counting from 1) of the statement a state executes
+    pub fn line_of(&self, state: usize) -> Option<usize> {
+        self.lines.get(state).copied().flatten()
+    }
+
+    /// The source text of the statement a state executes
+    pub fn statement_of(&self, state: usize) -> Option<&str> {
+        self.line_of(state).map(|line| self.source[line - 1].trim())
+    }
+}
+
+impl From<Program> for TuringMachine {
+    fn from(program: Program) -> Self { program.machine }
+}
+
+enum Instruction {
+    Move(i32),
+    Write(char),
+    /// Continue with the next instruction on any of the symbols, otherwise jump
+    Branch(Vec<char>, usize),
+    Jump(usize),
+    Goto(String),
+    Accept,
+    Reject
+}
+
+enum Block {
+    If { line: usize, branch: usize, else_jump: Option<usize> },
+    While { line: usize, test: usize }
+}
+
+/// Where control goes once an instruction is done
+#[derive(Clone, Copy)]
+enum Target {
+    State(usize),
+    Accept,
+    Reject
+}
+
+/// Compile a program into a machine
+pub fn compile(source: &str) -> Result<Program, TmError> {
+    let mut language: Option<Vec<char>> = None;
+    let mut tape_symbols = vec!['_', '>'];
+    let mut instructions: Vec<(usize, Instruction)> = vec![];
+    let mut labels: HashMap<String, usize> = HashMap::new();
+    let mut blocks: Vec<Block> = vec![];
+
+    for (i, text) in source.lines().enumerate() {
+        let line = i + 1;
+        let text = match text.find("//") {
+            Some(val) => &text[..val],
+            None => text
+        };
+        let words: Vec<&str> = text.split_whitespace().collect();
+
+        if words.is_empty() {
+            continue;
+        }
+
+        let error = |message: String| TmError::new(format!("Line {}: {}", line, message));
+        if language.is_none() && words[0] != "language" {
+            return Err(error("Language must come first.".to_string()));
+        }
+
+        let symbols = |words: &[&str]| -> Result<Vec<char>, TmError> {
+            words.iter().map(|word| {
+                let mut chars = word.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(c),
+                    _ => Err(error(format!("{} is not a single symbol.", word)))
+                }
+            }).collect()
+        };
+
+        match words[0] {
+            "language" | "tape" if !instructions.is_empty() || !labels.is_empty() => {
+                return Err(error(format!("The {} line must come before the first statement.", words[0])));
+            },
+            "language" => {
+                if language.is_some() {
+                    return Err(error("Language is declared twice.".to_string()));
+                }
+                language = Some(symbols(&words[1..])?);
+            },
+            "tape" => tape_symbols.extend(symbols(&words[1..])?),
+            "move" => {
+                let distance = match words.get(2) {
+                    Some(count) => match count.parse::<i32>() {
+                        Ok(val) if val > 0 && words.len() == 3 => val,
+                        _ => return Err(error(format!("Invalid move distance {}.", count)))
+                    },
+                    None => 1
+                };
+
+                match words.get(1) {
+                    Some(&"left") => instructions.push((line, Instruction::Move(-distance))),
+                    Some(&"right") => instructions.push((line, Instruction::Move(distance))),
+                    _ => return Err(error("Move must be followed by left or right.".to_string()))
+                }
+            },
+            "write" => match symbols(&words[1..])?[..] {
+                [symbol] => instructions.push((line, Instruction::Write(symbol))),
+                _ => return Err(error("Write must be followed by a single symbol.".to_string()))
+            },
+            "if" | "while" => {
+                if words.get(1) != Some(&"read") || words.len() < 3 {
+                    return Err(error(format!("Statement {} must be followed by read and at least one symbol.", words[0])));
+                }
+
+                let branch = instructions.len();
+                instructions.push((line, Instruction::Branch(symbols(&words[2..])?, 0)));
+                blocks.push(if words[0] == "if" {
+                    Block::If { line, branch, else_jump: None }
+                } else {
+                    Block::While { line, test: branch }
+                });
+            },
+            "else" if words.len() == 1 => match blocks.last_mut() {
+                Some(Block::If { branch, else_jump: else_jump @ None, .. }) => {
+                    *else_jump = Some(instructions.len());
+                    instructions.push((line, Instruction::Jump(0)));
+                    let target = instructions.len();
+                    if let Instruction::Branch(_, otherwise) = &mut instructions[*branch].1 {
+                        *otherwise = target;
+                    }
+                },
+                _ => return Err(error("Else without a matching if.".to_string()))
+            },
+            "end" if words.len() == 1 => match blocks.pop() {
+                Some(Block::If { branch, else_jump, .. }) => {
+                    let target = instructions.len();
+                    match else_jump {
+                        Some(jump) => instructions[jump].1 = Instruction::Jump(target),
+                        None => if let Instruction::Branch(_, otherwise) = &mut instructions[branch].1 {
+                            *otherwise = target;
+                        }
+                    }
+                },
+                Some(Block::While { test, .. }) => {
+                    instructions.push((line, Instruction::Jump(test)));
+                    let target = instructions.len();
+                    if let Instruction::Branch(_, otherwise) = &mut instructions[test].1 {
+                        *otherwise = target;
+                    }
+                },
+                None => return Err(error("End without a matching if or while.".to_string()))
+            },
+            "goto" if words.len() == 2 => instructions.push((line, Instruction::Goto(words[1].to_string()))),
+            "accept" if words.len() == 1 => instructions.push((line, Instruction::Accept)),
+            "reject" if words.len() == 1 => instructions.push((line, Instruction::Reject)),
+            label if words.len() == 1 && label.len() > 1 && label.ends_with(':') => {
+                let name = &label[..label.len() - 1];
+                if labels.insert(name.to_string(), instructions.len()).is_some() {
+                    return Err(error(format!("Label {} is defined twice.", name)));
+                }
+            },
+            _ => return Err(error(format!("Unknown statement {}.", text.trim())))
+        }
+    }
+
+    if let Some(block) = blocks.last() {
+        return Err(match block {
+            Block::If { line, .. } => TmError::new(format!("Line {}: If is missing its end.", line)),
+            Block::While { line, .. } => TmError::new(format!("Line {}: While is missing its end.", line))
+        });
+    }
+
+    let language = match language {
+        Some(val) => val,
+        None => return Err(TmError::new("Program must declare its language.".to_string()))
+    };
+
+    let symbols: Vec<char> = tape_symbols.iter().chain(language.iter()).copied().collect();
+    for (i, symbol) in symbols.iter().enumerate() {
+        if symbols[..i].contains(symbol) {
+            return Err(TmError::new(format!("Symbol {} is declared more than once.", symbol)));
+        }
+    }
+
+    // Resolve labels and check symbols before assigning states
+    for (line, instruction) in instructions.iter_mut() {
+        let used = match instruction {
+            Instruction::Write(symbol) => vec![*symbol],
+            Instruction::Branch(read, _) => read.clone(),
+            _ => vec![]
+        };
+
+        if let Some(symbol) = used.iter().find(|symbol| !symbols.contains(symbol)) {
+            return Err(TmError::new(format!("Line {}: Symbol {} is not declared.", line, symbol)));
+        }
+
+        if let Instruction::Goto(label) = instruction {
+            match labels.get(label) {
+                Some(target) => *instruction = Instruction::Jump(*target),
+                None => return Err(TmError::new(format!("Line {}: Unknown label {}.", line, label)))
+            }
+        }
+    }
+
+    // Only instructions that do something take a state, after the start, accept and reject states
+    let mut state_of = vec![None; instructions.len()];
+    let mut names = vec![ "start".to_string(), "accept".to_string(), "reject".to_string() ];
+    let mut lines = vec![ None, None, None ];
+
+    for (i, (line, instruction)) in instructions.iter().enumerate() {
+        if matches!(instruction, Instruction::Move(_) | Instruction::Write(_) | Instruction::Branch(..)) {
+            state_of[i] = Some(names.len());
+            names.push(format!("{}: {}", line, source.lines().nth(line - 1).unwrap_or_default().trim()));
+            lines.push(Some(*line));
+        }
+    }
+
+    let resolve = |start: usize| -> Result<Target, TmError> {
+        let mut current = start;
+        let mut visited = vec![];
+
+        loop {
+            match instructions.get(current) {
+                None => return Ok(Target::Reject),
+                Some((_, Instruction::Accept)) => return Ok(Target::Accept),
+                Some((_, Instruction::Reject)) => return Ok(Target::Reject),
+                Some((line, Instruction::Jump(target))) => {
+                    if visited.contains(&current) {
+                        return Err(TmError::new(format!("Line {}: Jumps loop forever without a statement.", line)));
+                    }
+                    visited.push(current);
+                    current = *target;
+                },
+                Some(_) => return Ok(Target::State(state_of[current].unwrap()))
+            }
+        }
+    };
+
+    let state_id = |target: Target| match target {
+        Target::State(val) => val,
+        Target::Accept => 1,
+        Target::Reject => 2
+    };
+
+    // The start state does nothing and continues in the first statement
+    let first = state_id(resolve(0)?);
+    let mut transitions = vec![
+        (0..symbols.len()).map(|symbol| Transition::new_transition(first, symbol, 0)).collect(),
+        vec![], // accept transitions
+        vec![], // reject transitions
+    ];
+
+    for (i, (_, instruction)) in instructions.iter().enumerate() {
+        if state_of[i].is_none() {
+            continue;
+        }
+
+        let next = state_id(resolve(i + 1)?);
+        let row = match instruction {
+            Instruction::Move(direction) => (0..symbols.len())
+                .map(|symbol| Transition::new_transition(next, symbol, *direction))
+                .collect(),
+            Instruction::Write(write) => {
+                let write = symbols.iter().position(|symbol| symbol == write).unwrap();
+                (0..symbols.len()).map(|_| Transition::new_transition(next, write, 0)).collect()
+            },
+            Instruction::Branch(read, otherwise) => {
+                let otherwise = state_id(resolve(*otherwise)?);
+                symbols.iter().enumerate().map(|(symbol, c)| {
+                    let target = if read.contains(c) { next } else { otherwise };
+                    Transition::new_transition(target, symbol, 0)
+                }).collect()
+            },
+            _ => unreachable!()
+        };
+
+        transitions.push(row);
+    }
+
+    let machine = TuringMachine::new(
+        names.into_iter().map(State::new_state).collect(),
+        language.into_iter().map(Symbol::new_symbol).collect(),
+        tape_symbols.into_iter().map(Symbol::new_symbol).collect(),
+        transitions,
+        0, 1, 2
+    );
+    machine.validate()?;
+
+    Ok(Program { machine, lines, source: source.lines().map(|line| line.to_string()).collect() })
+}
@@ -0,0 +1,319 @@
+//! # Regular Languages
+//!
+//! Deterministic finite automata and regular expressions, converted into machines that scan the
+//! input once from left to right and halt on the first blank.
+//!
+//! Regular expressions are written over the language symbols with
+//!
+//! * `ab` - concatenation
+//! * `a|b` - alternation
+//! * `a*`, `a+`, `a?` - zero or more, one or more, zero or one
+//! * `(a)` - grouping, with `()` matching the empty word
+//! * `.` - any language symbol
+//! * `\c` - the symbol `c`, even when it is one of the operators above
+
+use std::collections::{ BTreeSet, HashMap };
+
+use crate::errors::TmError;
+use crate::util::{ State, Symbol, Transition };
+use super::machine::TuringMachine;
+
+/// A complete deterministic finite automaton over a set of language symbols
+pub struct Dfa {
+    alphabet: Vec<char>,
+    transitions: Vec<Vec<usize>>,
+    start: usize,
+    accepting: Vec<bool>
+}
+
+impl Dfa {
+    /// Create a DFA where `transitions[q][i]` is the state entered from `q` on `alphabet[i]`
+    pub fn new(alphabet: Vec<char>, transitions: Vec<Vec<usize>>, start: usize, accepting: &[usize]) -> Result<Self, TmError> {
+        for (i, c) in alphabet.iter().enumerate() {
+            if *c == '_' || *c == '>' {
+                return Err(TmError::new(format!("Symbol {} is reserved and cannot be in the alphabet.", c)));
+            }
+
+            if alphabet[..i].contains(c) {
+                return Err(TmError::new(format!("Symbol {} appears more than once in the alphabet.", c)));
+            }
+        }
+
+        if start >= transitions.len() {
+            return Err(TmError::new(format!("Start state {} does not exist.", start)));
+        }
+
+        for (state, row) in transitions.iter().enumerate() {
+            if row.len() != alphabet.len() {
+                return Err(TmError::new(format!("State {} has {} transitions for {} symbols.", state, row.len(), alphabet.len())));
+            }
+
+            if let Some(next) = row.iter().find(|next| **next >= transitions.len()) {
+                return Err(TmError::new(format!("State {} moves to missing state {}.", state, next)));
+            }
+        }
+
+        let mut accepting_states = vec![false; transitions.len()];
+        for state in accepting {
+            match accepting_states.get_mut(*state) {
+                Some(val) => *val = true,
+                None => return Err(TmError::new(format!("Accepting state {} does not exist.", state)))
+            }
+        }
+
+        Ok(Dfa { alphabet, transitions, start, accepting: accepting_states })
+    }
+
+    /// Build the DFA of a regular expression over `alphabet` with the subset construction
+    pub fn from_regex(pattern: &str, alphabet: &[char]) -> Result<Self, TmError> {
+        let regex = Parser { chars: pattern.chars().collect(), position: 0, alphabet }.parse()?;
+
+        let mut nfa = Nfa { edges: vec![] };
+        let (nfa_start, nfa_end) = nfa.add(&regex, alphabet.len());
+
+        let start = nfa.closure([nfa_start].into_iter().collect());
+        let mut ids: HashMap<BTreeSet<usize>, usize> = HashMap::new();
+        let mut subsets = vec![start.clone()];
+        let mut transitions: Vec<Vec<usize>> = vec![];
+        ids.insert(start, 0);
+
+        while transitions.len() < subsets.len() {
+            let subset = subsets[transitions.len()].clone();
+            let row = (0..alphabet.len()).map(|symbol| {
+                let moved = subset.iter()
+                    .flat_map(|state| nfa.edges[*state].iter())
+                    .filter(|(read, _)| *read == Some(symbol))
+                    .map(|(_, next)| *next)
+                    .collect();
+                let next = nfa.closure(moved);
+
+                *ids.entry(next.clone()).or_insert_with(|| {
+                    subsets.push(next);
+                    subsets.len() - 1
+                })
+            }).collect();
+            transitions.push(row);
+        }
+
+        let accepting: Vec<usize> = (0..subsets.len()).filter(|i| subsets[*i].contains(&nfa_end)).collect();
+        Dfa::new(alphabet.to_vec(), transitions, 0, &accepting)
+    }
+
+    pub fn view_alphabet(&self) -> &Vec<char> { &self.alphabet }
+    pub fn view_transitions(&self) -> &Vec<Vec<usize>> { &self.transitions }
+    pub fn view_start_state(&self) -> usize { self.start }
+    pub fn view_accepting_states(&self) -> &Vec<bool> { &self.accepting }
+
+    /// Run the automaton directly on a word
+    pub fn accepts(&self, word: &str) -> Result<bool, TmError> {
+        let mut state = self.start;
+        for c in word.chars() {
+            match self.alphabet.iter().position(|symbol| *symbol == c) {
+                Some(val) => state = self.transitions[state][val],
+                None => return Err(TmError::new(format!("Symbol {} is not in the alphabet.", c)))
+            }
+        }
+
+        Ok(self.accepting[state])
+    }
+
+    /// A machine that steps off the start marker, follows the automaton one cell per step and
+    /// halts on the first blank, taking the length of the word plus two steps
+    pub fn to_machine(&self) -> TuringMachine {
+        let symbols = self.alphabet.len() + 2;
+        let (accept, reject) = (1, 2);
+        let state_id = |state: usize| state + 3;
+
+        let mut states = vec![
+            State::new_state("start".to_string()),
+            State::new_state("accept".to_string()),
+            State::new_state("reject".to_string()),
+        ];
+        let mut start_row: Vec<Transition> = (0..symbols).map(|symbol| Transition::new_transition(reject, symbol, 0)).collect();
+        start_row[1] = Transition::new_transition(state_id(self.start), 1, 1);
+        let mut transitions = vec![start_row, vec![], vec![]];
+
+        for (state, row) in self.transitions.iter().enumerate() {
+            states.push(State::new_state(format!("q{}", state)));
+
+            let halt = if self.accepting[state] { accept } else { reject };
+            let mut machine_row = vec![
+                Transition::new_transition(halt, 0, 0),
+                Transition::new_transition(reject, 1, 0),
+            ];
+            machine_row.extend(row.iter().enumerate().map(|(symbol, next)| Transition::new_transition(state_id(*next), symbol + 2, 1)));
+            transitions.push(machine_row);
+        }
+
+        TuringMachine::new(
+            states,
+            self.alphabet.iter().map(|c| Symbol::new_symbol(*c)).collect(),
+            vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ],
+            transitions,
+            0, accept, reject
+        )
+    }
+}
+
+/// Build the scanning machine of a regular expression over `alphabet`
+pub fn regex_to_machine(pattern: &str, alphabet: &[char]) -> Result<TuringMachine, TmError> {
+    Ok(Dfa::from_regex(pattern, alphabet)?.to_machine())
+}
+
+#[derive(Clone)]
+enum Regex {
+    Empty,
+    Symbol(usize),
+    Any,
+    Concat(Box<Regex>, Box<Regex>),
+    Alternate(Box<Regex>, Box<Regex>),
+    Star(Box<Regex>)
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    position: usize,
+    alphabet: &'a [char]
+}
+
+impl Parser<'_> {
+    fn parse(mut self) -> Result<Regex, TmError> {
+        let regex = self.alternation()?;
+        match self.chars.get(self.position) {
+            None => Ok(regex),
+            Some(c) => Err(TmError::new(format!("Unexpected {} at position {} of the regular expression.", c, self.position)))
+        }
+    }
+
+    fn alternation(&mut self) -> Result<Regex, TmError> {
+        let mut regex = self.concatenation()?;
+        while self.chars.get(self.position) == Some(&'|') {
+            self.position += 1;
+            regex = Regex::Alternate(Box::new(regex), Box::new(self.concatenation()?));
+        }
+        Ok(regex)
+    }
+
+    fn concatenation(&mut self) -> Result<Regex, TmError> {
+        let mut regex = Regex::Empty;
+        while let Some(c) = self.chars.get(self.position) {
+            if *c == '|' || *c == ')' {
+                break;
+            }
+
+            let repeated = self.repetition()?;
+            regex = match regex {
+                Regex::Empty => repeated,
+                other => Regex::Concat(Box::new(other), Box::new(repeated))
+            };
+        }
+        Ok(regex)
+    }
+
+    fn repetition(&mut self) -> Result<Regex, TmError> {
+        let mut regex = self.atom()?;
+        while let Some(c) = self.chars.get(self.position) {
+            regex = match c {
+                '*' => Regex::Star(Box::new(regex)),
+                '+' => Regex::Concat(Box::new(regex.clone()), Box::new(Regex::Star(Box::new(regex)))),
+                '?' => Regex::Alternate(Box::new(regex), Box::new(Regex::Empty)),
+                _ => break
+            };
+            self.position += 1;
+        }
+        Ok(regex)
+    }
+
+    fn atom(&mut self) -> Result<Regex, TmError> {
+        let position = self.position;
+        let c = self.chars[position];
+        self.position += 1;
+
+        match c {
+            '(' => {
+                let regex = self.alternation()?;
+                if self.chars.get(self.position) != Some(&')') {
+                    return Err(TmError::new(format!("Group opened at position {} is never closed.", position)));
+                }
+                self.position += 1;
+                Ok(regex)
+            },
+            '.' => Ok(Regex::Any),
+            '*' | '+' | '?' => Err(TmError::new(format!("Nothing to repeat at position {}.", position))),
+            '\\' => match self.chars.get(self.position) {
+                Some(escaped) => {
+                    self.position += 1;
+                    self.symbol(*escaped, position)
+                },
+                None => Err(TmError::new("Regular expression ends with an escape.".to_string()))
+            },
+            other => self.symbol(other, position)
+        }
+    }
+
+    fn symbol(&self, c: char, position: usize) -> Result<Regex, TmError> {
+        match self.alphabet.iter().position(|symbol| *symbol == c) {
+            Some(val) => Ok(Regex::Symbol(val)),
+            None => Err(TmError::new(format!("Symbol {} at position {} is not in the alphabet.", c, position)))
+        }
+    }
+}
+
+/// A Thompson automaton where an edge reading `None` is an epsilon move
+struct Nfa {
+    edges: Vec<Vec<(Option<usize>, usize)>>
+}
+
+impl Nfa {
+    fn state(&mut self) -> usize {
+        self.edges.push(vec![]);
+        self.edges.len() - 1
+    }
+
+    /// Add the states of a regular expression, returning its start and end states
+    fn add(&mut self, regex: &Regex, alphabet_size: usize) -> (usize, usize) {
+        let start = self.state();
+        let end = self.state();
+
+        match regex {
+            Regex::Empty => self.edges[start].push((None, end)),
+            Regex::Symbol(symbol) => self.edges[start].push((Some(*symbol), end)),
+            Regex::Any => for symbol in 0..alphabet_size {
+                self.edges[start].push((Some(symbol), end));
+            },
+            Regex::Concat(first, second) => {
+                let (first_start, first_end) = self.add(first, alphabet_size);
+                let (second_start, second_end) = self.add(second, alphabet_size);
+                self.edges[start].push((None, first_start));
+                self.edges[first_end].push((None, second_start));
+                self.edges[second_end].push((None, end));
+            },
+            Regex::Alternate(left, right) => for option in [left, right] {
+                let (option_start, option_end) = self.add(option, alphabet_size);
+                self.edges[start].push((None, option_start));
+                self.edges[option_end].push((None, end));
+            },
+            Regex::Star(inner) => {
+                let (inner_start, inner_end) = self.add(inner, alphabet_size);
+                self.edges[start].push((None, inner_start));
+                self.edges[start].push((None, end));
+                self.edges[inner_end].push((None, inner_start));
+                self.edges[inner_end].push((None, end));
+            }
+        }
+
+        (start, end)
+    }
+
+    fn closure(&self, mut states: BTreeSet<usize>) -> BTreeSet<usize> {
+        let mut pending: Vec<usize> = states.iter().copied().collect();
+        while let Some(state) = pending.pop() {
+            for (read, next) in &self.edges[state] {
+                if read.is_none() && states.insert(*next) {
+                    pending.push(*next);
+                }
+            }
+        }
+        states
+    }
+}
@@ -0,0 +1,111 @@
+//! # Transducers
+//!
+//! Machines computing functions rather than deciding languages. A transducer has a dedicated
+//! halting state, and when the machine enters it the result is read from the tape between the start
+//! marker and the first blank. Halting in any other way leaves the function undefined on the input.
+//!
+//! Numbers and tuples are written to and read from words with the [`encoding`](super::encoding)
+//! module.
+
+use crate::errors::TmError;
+use crate::util::{ ComputationStatus, ComputationClock };
+use super::computation::Computation;
+use super::machine::TuringMachine;
+
+pub struct Transducer {
+    machine: TuringMachine,
+    halt_state: usize
+}
+
+impl Transducer {
+    /// Create a transducer that halts with its output when `machine` enters `halt_state`, which may
+    /// be the accept state
+    pub fn new(machine: TuringMachine, halt_state: usize) -> Result<Self, TmError> {
+        machine.validate()?;
+
+        if halt_state >= machine.view_states().len() {
+            return Err(TmError::new(format!("State {} does not exist.", halt_state)));
+        }
+
+        if halt_state == machine.view_reject_state() {
+            return Err(TmError::new("The halting state cannot be the reject state.".to_string()));
+        }
+
+        Ok(Self { machine, halt_state })
+    }
+
+    /// Construct a bounded computation of the function on an input
+    pub fn compute(&self, input: &str, limits: (Option<usize>, Option<usize>)) -> Result<TransducerComputation<'_>, TmError> {
+        TransducerComputation::start(self, input, limits)
+    }
+
+    /// Compute the function on an input, failing when the machine halts without an output or
+    /// reaches a limit
+    pub fn apply(&self, input: &str, limits: (Option<usize>, Option<usize>)) -> Result<String, TmError> {
+        let mut computation = self.compute(input, limits)?;
+        computation.run();
+
+        computation.view_output()
+            .ok_or_else(|| TmError::new(format!("The machine ended with {:?} on {} instead of an output.", computation.view_status(), input)))
+    }
+
+    pub fn view_machine(&self) -> &TuringMachine { &self.machine }
+    pub fn view_halt_state(&self) -> usize { self.halt_state }
+}
+
+pub struct TransducerComputation<'a> {
+    transducer: &'a Transducer,
+    computation: Computation<'a>,
+    status: ComputationStatus
+}
+
+impl TransducerComputation<'_> {
+    pub fn view_transducer(&self) -> &Transducer { self.transducer }
+    pub fn view_computation(&self) -> &Computation<'_> { &self.computation }
+    pub fn view_current_state(&self) -> usize { self.computation.view_current_state() }
+    pub fn view_clock(&self) -> &ComputationClock { self.computation.view_clock() }
+
+    /// [`ComputationStatus::Accept`] once the machine halts with an output, and
+    /// [`ComputationStatus::Reject`] when it halts in any other way
+    pub fn view_status(&self) -> &ComputationStatus { &self.status }
+
+    /// The result, once the machine has entered the halting state
+    pub fn view_output(&self) -> Option<String> {
+        (self.status == ComputationStatus::Accept).then(|| self.computation.view_output())
+    }
+}
+
+impl<'a> TransducerComputation<'a> {
+    /// Create a bounded computation from a transducer and input
+    pub fn start(transducer: &'a Transducer, input: &str, limits: (Option<usize>, Option<usize>)) -> Result<Self, TmError> {
+        let computation = transducer.machine.bounded_compute(input, limits)?;
+        let status = if transducer.halt_state == transducer.machine.view_start_state() {
+            ComputationStatus::Accept
+        } else {
+            ComputationStatus::Executing
+        };
+
+        Ok(TransducerComputation { transducer, computation, status })
+    }
+
+    /// Execute the next transition, halting if it enters the halting state
+    pub fn step(&mut self) {
+        if self.status != ComputationStatus::Executing {
+            return;
+        }
+
+        self.computation.step();
+        self.status = match self.computation.view_status() {
+            ComputationStatus::Executing | ComputationStatus::Accept if self.computation.view_current_state() == self.transducer.halt_state => ComputationStatus::Accept,
+            ComputationStatus::Accept => ComputationStatus::Reject,
+            status => *status
+        };
+    }
+
+    /// Run the Computation until it halts
+    pub fn run(&mut self) {
+        while self.status == ComputationStatus::Executing {
+            self.step();
+        }
+    }
+}
@@ -0,0 +1,416 @@
+//! # Universal Turing Machine
+//!
+//! A standard encoding $\left< M, w \right>$ of a machine and word as a single string, and a prebuilt
+//! [`TuringMachine`] that simulates any encoded machine on its encoded word.
+//!
+//! The encoding is made of four sections separated by `#`
+//!
+//! ```text
+//! header # transitions # key # tape
+//! ```
+//!
+//! * header - `n,t,c_1,...,c_m,a,r` in binary: the number of states, the number of tape symbols, the
+//!   character code of every symbol and the accept and reject states
+//! * transitions - one `;key value` entry per transition, where the key is the state and read
+//!   symbol and the value the next state, written symbol, moves (`S`, or one `L`/`R` per cell) and
+//!   whether the next state accepts (`+`), rejects (`-`) or neither (`.`)
+//! * key - the current state followed by the symbol under the head
+//! * tape - one `,`-prefixed cell per tape cell, with the head cell prefixed by `*` instead
+//!
+//! States are written with a fixed width of $\lceil \log_2 n \rceil$ bits and symbols with
+//! $\lceil \log_2 m \rceil$ bits, so the blank is always a string of `0`s. State names are not part of
+//! the encoding and decode as `q0`, `q1`, ...
+
+use std::collections::HashMap;
+
+use crate::errors::TmError;
+use crate::util::{ State, Symbol, Transition };
+use super::builder::Builder;
+use super::computation::encode_word;
+use super::machine::TuringMachine;
+
+const ACCEPT: char = '+';
+const REJECT: char = '-';
+const CONTINUE: char = '.';
+
+/// Tape symbols of the universal machine, used to mark progress while shuttling bits around
+const UNIVERSAL_TAPE_SYMBOLS: [char; 8] = ['_', '>', 'o', 'i', ':', 'l', 'r', 's'];
+
+/// The fixed alphabet of every encoding
+const UNIVERSAL_LANGUAGE_SYMBOLS: [char; 12] = ['0', '1', '#', ';', ',', '*', 'L', 'R', 'S', ACCEPT, REJECT, CONTINUE];
+
+/// Symbols that can appear in the transitions section while the universal machine runs
+const TRANSITION_CHARS: &str = "01oi;:LRSlrs+-.";
+
+/// Encode a machine and a word as $\left< M, w \right>$
+pub fn encode(machine: &TuringMachine, word: &str) -> Result<String, TmError> {
+    let states = machine.view_states().len();
+    let symbols = machine.view_tape_symbols().len() + machine.view_language_symbols().len();
+    let state_bits = width(states);
+    let symbol_bits = width(symbols);
+    let tape = encode_word(machine, word)?;
+
+    let mut header = vec![ binary(states), binary(machine.view_tape_symbols().len()) ];
+    header.extend(machine.view_tape_symbols().iter()
+        .chain(machine.view_language_symbols().iter())
+        .map(|symbol| binary(symbol.as_char() as usize)));
+    header.push(binary(machine.view_accept_state()));
+    header.push(binary(machine.view_reject_state()));
+
+    let mut encoding = header.join(",");
+    encoding.push('#');
+
+    for (state, row) in machine.view_transitions().iter().enumerate() {
+        for (symbol, transition) in row.iter().enumerate() {
+            if state >= states || symbol >= symbols || transition.next_state_id() >= states || transition.write_symbol_id() >= symbols {
+                return Err(TmError::new(
+                    format!("Transition {} from state {} on symbol {} is out of range.", transition, state, symbol)
+                ));
+            }
+
+            encoding.push(';');
+            encoding.push_str(&fixed_binary(state, state_bits));
+            encoding.push_str(&fixed_binary(symbol, symbol_bits));
+            encoding.push_str(&fixed_binary(transition.next_state_id(), state_bits));
+            encoding.push_str(&fixed_binary(transition.write_symbol_id(), symbol_bits));
+
+            let distance = transition.direction().unsigned_abs() as usize;
+            match transition.direction() {
+                0 => encoding.push('S'),
+                d if d < 0 => encoding.push_str(&"L".repeat(distance)),
+                _ => encoding.push_str(&"R".repeat(distance))
+            }
+
+            encoding.push(if transition.next_state_id() == machine.view_accept_state() {
+                ACCEPT
+            } else if transition.next_state_id() == machine.view_reject_state() {
+                REJECT
+            } else {
+                CONTINUE
+            });
+        }
+    }
+
+    encoding.push('#');
+    encoding.push_str(&fixed_binary(machine.view_start_state(), state_bits));
+    encoding.push_str(&fixed_binary(tape[0], symbol_bits));
+    encoding.push('#');
+
+    for (i, symbol) in tape.iter().enumerate() {
+        encoding.push(if i == 0 { '*' } else { ',' });
+        encoding.push_str(&fixed_binary(*symbol, symbol_bits));
+    }
+
+    Ok(encoding)
+}
+
+/// Decode $\left< M, w \right>$ back into a machine and a word
+pub fn decode(encoding: &str) -> Result<(TuringMachine, String), TmError> {
+    let sections: Vec<&str> = encoding.split('#').collect();
+    if sections.len() != 4 {
+        return Err(TmError::new(format!("Expected 4 sections in encoding but found {}.", sections.len())));
+    }
+
+    let header = sections[0].split(',').map(parse_binary).collect::<Result<Vec<usize>, TmError>>()?;
+    if header.len() < 4 || header[1] > header.len() - 4 {
+        return Err(TmError::new("Malformed header in encoding.".to_string()));
+    }
+
+    let states = header[0];
+    let tape_symbol_count = header[1];
+    let codes = &header[2..header.len() - 2];
+    let accept_state = header[header.len() - 2];
+    let reject_state = header[header.len() - 1];
+
+    let mut characters = vec![];
+    for code in codes {
+        match u32::try_from(*code).ok().and_then(char::from_u32) {
+            Some(val) => characters.push(val),
+            None => return Err(TmError::new(format!("Invalid character code {} in encoding.", code)))
+        }
+    }
+
+    let symbols = characters.len();
+    let state_bits = width(states);
+    let symbol_bits = width(symbols);
+    let key_bits = state_bits + symbol_bits;
+
+    let mut entries: HashMap<(usize, usize), Transition> = HashMap::new();
+    for entry in sections[1].split(';').skip(1) {
+        let bits_end = entry.find(|c| c != '0' && c != '1').unwrap_or(entry.len());
+        if bits_end != 2 * key_bits || entry.len() < bits_end + 2 || !entry.ends_with([ACCEPT, REJECT, CONTINUE]) {
+            return Err(TmError::new(format!("Malformed transition ;{} in encoding.", entry)));
+        }
+
+        let state = parse_binary(&entry[0..state_bits])?;
+        let symbol = parse_binary(&entry[state_bits..key_bits])?;
+        let next_state = parse_binary(&entry[key_bits..key_bits + state_bits])?;
+        let write_symbol = parse_binary(&entry[key_bits + state_bits..bits_end])?;
+
+        let moves = &entry[bits_end..entry.len() - 1];
+        let direction = if moves == "S" {
+            0
+        } else if !moves.is_empty() && moves.chars().all(|c| c == 'L') {
+            -(moves.len() as i32)
+        } else if !moves.is_empty() && moves.chars().all(|c| c == 'R') {
+            moves.len() as i32
+        } else {
+            return Err(TmError::new(format!("Malformed moves {} in encoding.", moves)));
+        };
+
+        if state >= states || next_state >= states || symbol >= symbols || write_symbol >= symbols {
+            return Err(TmError::new(format!("Transition ;{} in encoding is out of range.", entry)));
+        }
+
+        entries.insert((state, symbol), Transition::new_transition(next_state, write_symbol, direction));
+    }
+
+    let mut transitions: Vec<Vec<Transition>> = vec![];
+    for state in 0..states {
+        let mut row = vec![];
+        while let Some(transition) = entries.remove(&(state, row.len())) {
+            row.push(transition);
+        }
+        transitions.push(row);
+    }
+
+    if let Some((state, symbol)) = entries.keys().next() {
+        return Err(TmError::new(
+            format!("Transition from state {} on symbol {} does not follow the previous symbols of its state.", state, symbol)
+        ));
+    }
+
+    let key = sections[2];
+    if key.len() != key_bits || !key.chars().all(|c| c == '0' || c == '1') {
+        return Err(TmError::new(format!("Malformed key {} in encoding.", key)));
+    }
+    let start_state = parse_binary(&key[0..state_bits])?;
+
+    if let Some(state) = [start_state, accept_state, reject_state].into_iter().find(|state| *state >= states) {
+        return Err(TmError::new(format!("State {} in encoding is out of range for {} states.", state, states)));
+    }
+
+    let mut word = String::new();
+    for (i, cell) in sections[3].split([',', '*']).skip(2).enumerate() {
+        let symbol = parse_binary(cell)?;
+        if cell.len() != symbol_bits || symbol >= symbols {
+            return Err(TmError::new(format!("Malformed cell {} at position {} in encoding.", cell, i)));
+        }
+        word.push(characters[symbol]);
+    }
+
+    let machine = TuringMachine::new(
+        (0..states).map(|i| State::new_state(format!("q{i}"))).collect(),
+        characters[tape_symbol_count..].iter().map(|c| Symbol::new_symbol(*c)).collect(),
+        characters[..tape_symbol_count].iter().map(|c| Symbol::new_symbol(*c)).collect(),
+        transitions,
+        start_state,
+        accept_state,
+        reject_state
+    );
+
+    machine.validate()?;
+    Ok((machine, word))
+}
+
+/// A universal Turing Machine, accepting $\left< M, w \right>$ exactly when $M$ accepts $w$
+///
+/// Rejects when $M$ rejects $w$ or reaches a missing transition, and runs forever when $M$ runs forever.
+pub fn universal_machine() -> TuringMachine {
+    let mut b = Builder::new(&UNIVERSAL_TAPE_SYMBOLS, &UNIVERSAL_LANGUAGE_SYMBOLS);
+    b.missing_direction(1);
+
+    // Match the key of the first remaining transition against the key section, bit by bit
+    let rewind_find = rewind(&mut b, "find");
+    let rewind_apply = rewind(&mut b, "apply");
+    let rewind_eliminate = rewind(&mut b, "eliminate");
+    b.rule("start", ">", None, 1, "header_find");
+    b.rule("find", "01oi:LRSlrs+-.", None, 1, "find");
+    b.rule("find", ";", None, 1, "compare");
+    b.rule("find", "#", None, 1, "reject");
+    b.rule("compare", "oi", None, 1, "compare");
+    b.rule("compare", "0", Some('o'), 1, "carry_0");
+    b.rule("compare", "1", Some('i'), 1, "carry_1");
+    for (bit, mark, other) in [("0", 'o', "1"), ("1", 'i', "0")] {
+        b.rule(&format!("carry_{bit}"), TRANSITION_CHARS, None, 1, &format!("carry_{bit}"));
+        b.rule(&format!("carry_{bit}"), "#", None, 1, &format!("match_{bit}"));
+        b.rule(&format!("match_{bit}"), "oi", None, 1, &format!("match_{bit}"));
+        b.rule(&format!("match_{bit}"), bit, Some(mark), 1, "key_end");
+        b.rule(&format!("match_{bit}"), other, None, -1, "mismatch");
+    }
+    b.rule("key_end", "#", None, -1, &rewind_apply);
+    b.rule("key_end", "01", None, -1, &rewind_find);
+    b.rule("mismatch", "o", Some('0'), -1, "mismatch");
+    b.rule("mismatch", "i", Some('1'), -1, "mismatch");
+    b.rule("mismatch", "01", None, -1, "mismatch");
+    b.rule("mismatch", "#", None, -1, &rewind_eliminate);
+    b.rule("eliminate", "01oi:LRSlrs+-.", None, 1, "eliminate");
+    b.rule("eliminate", ";", Some(':'), 1, "find");
+
+    // Overwrite the key section with the next state and written symbol of the matched transition
+    b.rule("apply", "01oi:LRSlrs+-.", None, 1, "apply");
+    b.rule("apply", ";", None, 1, "copy_value");
+    b.rule("copy_value", "oi", None, 1, "copy_value");
+    b.rule("copy_value", "0", Some('o'), 1, "value_0");
+    b.rule("copy_value", "1", Some('i'), 1, "value_1");
+    b.rule("copy_value", "LRS", None, 1, "to_key");
+    for bit in ["0", "1"] {
+        b.rule(&format!("value_{bit}"), TRANSITION_CHARS, None, 1, &format!("value_{bit}"));
+        b.rule(&format!("value_{bit}"), "#", None, 1, &format!("put_{bit}"));
+        b.rule(&format!("put_{bit}"), "01", None, 1, &format!("put_{bit}"));
+        b.rule(&format!("put_{bit}"), "oi", Some(bit.chars().next().unwrap()), -1, &rewind_apply);
+    }
+
+    // Copy the written symbol from the end of the key section into the head cell
+    let rewind_move = rewind(&mut b, "move");
+    b.rule("to_key", TRANSITION_CHARS, None, 1, "to_key");
+    b.rule("to_key", "#", None, 1, "over_key");
+    b.rule("over_key", "01oi", None, 1, "over_key");
+    b.rule("over_key", "#", None, -1, "write_pick");
+    b.rule("write_pick", "oi", None, -1, "write_pick");
+    b.rule("write_pick", "0", Some('o'), 1, "write_0");
+    b.rule("write_pick", "1", Some('i'), 1, "write_1");
+    for (bit, mark) in [("0", 'o'), ("1", 'i')] {
+        b.rule(&format!("write_{bit}"), "01oi#,", None, 1, &format!("write_{bit}"));
+        b.rule(&format!("write_{bit}"), "*", None, 1, &format!("cell_end_{bit}"));
+        b.rule(&format!("cell_end_{bit}"), "01oi", None, 1, &format!("cell_end_{bit}"));
+        b.rule(&format!("cell_end_{bit}"), ",_", None, -1, &format!("cell_put_{bit}"));
+        b.rule(&format!("cell_put_{bit}"), "oi", None, -1, &format!("cell_put_{bit}"));
+        b.rule(&format!("cell_put_{bit}"), "01", Some(mark), -1, "cell_full");
+    }
+    b.rule("cell_full", "*", None, 1, "unmark_cell");
+    b.rule("cell_full", "01", None, -1, "back_to_key");
+    b.rule("back_to_key", ",*01oi", None, -1, "back_to_key");
+    b.rule("back_to_key", "#", None, -1, "write_pick");
+    b.rule("unmark_cell", "o", Some('0'), 1, "unmark_cell");
+    b.rule("unmark_cell", "i", Some('1'), 1, "unmark_cell");
+    b.rule("unmark_cell", "01", None, 1, "unmark_cell");
+    b.rule("unmark_cell", ",_", None, -1, "unmark_back");
+    b.rule("unmark_back", ",*01oi", None, -1, "unmark_back");
+    b.rule("unmark_back", "#", None, -1, "unmark_key");
+    b.rule("unmark_key", "o", Some('0'), -1, "unmark_key");
+    b.rule("unmark_key", "i", Some('1'), -1, "unmark_key");
+    b.rule("unmark_key", "01", None, -1, "unmark_key");
+    b.rule("unmark_key", "#", None, -1, &rewind_move);
+
+    // Move the head marker once per move of the transition, then halt or continue
+    b.rule("move", "01oi:LRSlrs+-.", None, 1, "move");
+    b.rule("move", ";", None, 1, "next_move");
+    b.rule("next_move", "01oilrs", None, 1, "next_move");
+    b.rule("next_move", "S", Some('s'), 1, "next_move");
+    b.rule("next_move", "L", Some('l'), 1, "move_left");
+    b.rule("next_move", "R", Some('r'), 1, "move_right");
+    b.rule("next_move", "+", None, 1, "accept");
+    b.rule("next_move", "-", None, 1, "reject");
+    b.rule("next_move", ".", None, 1, "read_head");
+    b.rule("move_left", "01oi:;LRSlrs+-.#,", None, 1, "move_left");
+    b.rule("move_left", "*", None, -1, "left_scan");
+    b.rule("left_scan", "01", None, -1, "left_scan");
+    b.rule("left_scan", ",", Some('*'), 1, "left_fix");
+    b.rule("left_scan", "#", None, -1, &rewind_move);
+    b.rule("left_fix", "01", None, 1, "left_fix");
+    b.rule("left_fix", "*", Some(','), -1, &rewind_move);
+    b.rule("move_right", "01oi:;LRSlrs+-.#,", None, 1, "move_right");
+    b.rule("move_right", "*", Some(','), 1, "right_scan");
+    b.rule("right_scan", "01", None, 1, "right_scan");
+    b.rule("right_scan", ",", Some('*'), -1, &rewind_move);
+    b.rule("right_scan", "_", Some('*'), -1, "new_cell");
+    b.rule("new_cell", "01oi", None, -1, "new_cell");
+    b.rule("new_cell", ",", None, 1, "new_cell_pick");
+    b.rule("new_cell_pick", "oi", None, 1, "new_cell_pick");
+    b.rule("new_cell_pick", "0", Some('o'), 1, "new_cell_append");
+    b.rule("new_cell_pick", "1", Some('i'), 1, "new_cell_append");
+    b.rule("new_cell_pick", "*", None, -1, "new_cell_unmark");
+    b.rule("new_cell_append", "01oi*", None, 1, "new_cell_append");
+    b.rule("new_cell_append", "_", Some('0'), -1, "new_cell_back");
+    b.rule("new_cell_back", "01oi*", None, -1, "new_cell_back");
+    b.rule("new_cell_back", ",", None, 1, "new_cell_pick");
+    b.rule("new_cell_unmark", "o", Some('0'), -1, "new_cell_unmark");
+    b.rule("new_cell_unmark", "i", Some('1'), -1, "new_cell_unmark");
+    b.rule("new_cell_unmark", "01", None, -1, "new_cell_unmark");
+    b.rule("new_cell_unmark", ",", None, -1, &rewind_move);
+
+    // Copy the symbol under the new head position into the end of the key section
+    let rewind_restore = rewind(&mut b, "restore");
+    b.rule("read_head", "01oi:;LRSlrs+-.#,", None, 1, "read_head");
+    b.rule("read_head", "*", None, 1, "read_end");
+    b.rule("read_end", "01oi", None, 1, "read_end");
+    b.rule("read_end", ",_", None, -1, "read_pick");
+    b.rule("read_pick", "oi", None, -1, "read_pick");
+    b.rule("read_pick", "0", Some('o'), -1, "read_check_0");
+    b.rule("read_pick", "1", Some('i'), -1, "read_check_1");
+    for (bit, mark) in [("0", 'o'), ("1", 'i')] {
+        b.rule(&format!("read_check_{bit}"), "*", None, -1, &format!("read_carry_last_{bit}"));
+        b.rule(&format!("read_check_{bit}"), "01", None, -1, &format!("read_carry_{bit}"));
+        for last in ["", "last_"] {
+            let carry = format!("read_carry_{last}{bit}");
+            let put = format!("read_put_{last}{bit}");
+            b.rule(&carry, ",*01oi", None, -1, &carry);
+            b.rule(&carry, "#", None, -1, &put);
+            b.rule(&put, "oi", None, -1, &put);
+            b.rule(&put, "01", Some(mark), 1, if last.is_empty() { "read_return" } else { "read_clean" });
+        }
+    }
+    b.rule("read_return", "01oi#,", None, 1, "read_return");
+    b.rule("read_return", "*", None, 1, "read_end");
+    b.rule("read_clean", "01oi", None, 1, "read_clean");
+    b.rule("read_clean", "#", None, -1, "read_unmark_key");
+    b.rule("read_unmark_key", "o", Some('0'), -1, "read_unmark_key");
+    b.rule("read_unmark_key", "i", Some('1'), -1, "read_unmark_key");
+    b.rule("read_unmark_key", "01", None, -1, "read_unmark_key");
+    b.rule("read_unmark_key", "#", None, 1, "read_to_cell");
+    b.rule("read_to_cell", "01oi#,", None, 1, "read_to_cell");
+    b.rule("read_to_cell", "*", None, 1, "read_unmark_cell");
+    b.rule("read_unmark_cell", "o", Some('0'), 1, "read_unmark_cell");
+    b.rule("read_unmark_cell", "i", Some('1'), 1, "read_unmark_cell");
+    b.rule("read_unmark_cell", "01", None, 1, "read_unmark_cell");
+    b.rule("read_unmark_cell", ",_", None, -1, &rewind_restore);
+
+    // Clear every mark left in the transitions section and look for the next transition
+    b.rule("restore", ":", Some(';'), 1, "restore");
+    b.rule("restore", "o", Some('0'), 1, "restore");
+    b.rule("restore", "i", Some('1'), 1, "restore");
+    b.rule("restore", "l", Some('L'), 1, "restore");
+    b.rule("restore", "r", Some('R'), 1, "restore");
+    b.rule("restore", "s", Some('S'), 1, "restore");
+    b.rule("restore", "01;LRS+-.", None, 1, "restore");
+    b.rule("restore", "#", None, -1, &rewind_find);
+
+    b.build()
+}
+
+/// Number of bits needed to write every id below `count`
+fn width(count: usize) -> usize {
+    match count {
+        0..=2 => 1,
+        _ => (usize::BITS - (count - 1).leading_zeros()) as usize
+    }
+}
+
+fn binary(value: usize) -> String {
+    format!("{:b}", value)
+}
+
+fn fixed_binary(value: usize, bits: usize) -> String {
+    format!("{:0>width$b}", value, width = bits)
+}
+
+fn parse_binary(bits: &str) -> Result<usize, TmError> {
+    match usize::from_str_radix(bits, 2) {
+        Ok(val) if !bits.starts_with('+') => Ok(val),
+        _ => Err(TmError::new(format!("Invalid binary number {} in encoding.", bits)))
+    }
+}
+
+/// Add states that return to the start of the transitions section and enter `target`
+fn rewind(b: &mut Builder, target: &str) -> String {
+    let rewind = format!("rewind_{target}");
+    let header = format!("header_{target}");
+    let everything = b.symbols_except(">");
+
+    b.rule(&rewind, &everything, None, -1, &rewind);
+    b.rule(&rewind, ">", None, 1, &header);
+    b.rule(&header, "01,", None, 1, &header);
+    b.rule(&header, "#", None, 1, target);
+    rewind
+}
@@ -0,0 +1,101 @@
+use crate::errors::TmError;
+use crate::util::{ ComputationStatus, ComputationClock };
+use super::machine::{ GridMachine, Heading, Movement };
+use super::store::SparseGrid;
+
+/// A computation of a [`GridMachine`], where the space of the clock is the area of the grid bounds
+pub struct GridComputation<'a> {
+    machine: &'a GridMachine,
+    current_state: usize,
+    grid: SparseGrid,
+    head: (i64, i64),
+    heading: Heading,
+    status: ComputationStatus,
+    clock: ComputationClock
+}
+
+impl GridComputation<'_> {
+    pub fn view_machine(&self) -> &GridMachine { self.machine }
+    pub fn view_current_state(&self) -> usize { self.current_state }
+    pub fn view_head_position(&self) -> (i64, i64) { self.head }
+    pub fn view_heading(&self) -> Heading { self.heading }
+    pub fn view_grid(&self) -> &SparseGrid { &self.grid }
+    pub fn view_status(&self) -> &ComputationStatus { &self.status }
+    pub fn view_clock(&self) -> &ComputationClock { &self.clock }
+
+    /// Draw the grid inside its bounds with the symbol characters of the machine, one line per row
+    pub fn render(&self) -> String {
+        let symbols = self.machine.view_symbols();
+        self.grid.rows().iter()
+            .map(|row| row.iter().map(|id| symbols[*id].as_char()).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl<'a> GridComputation<'a> {
+    /// Create a bounded computation from a machine and grid, with the head at `(0, 0)` facing north
+    pub fn start(machine: &'a GridMachine, grid: SparseGrid, limits: (Option<usize>, Option<usize>)) -> Result<Self, TmError> {
+        if let Some(symbol) = grid.rows().iter().flatten().find(|symbol| **symbol >= machine.view_symbols().len()) {
+            return Err(TmError::new(format!("Invalid symbol id {} found on the grid.", symbol)));
+        }
+
+        Ok(GridComputation {
+            machine,
+            current_state: machine.view_start_state(),
+            clock: ComputationClock::clock(limits.0, limits.1, grid.bounds().area()),
+            grid,
+            head: (0, 0),
+            heading: Heading::North,
+            status: ComputationStatus::Executing
+        })
+    }
+
+    /// Execute the next transition from the current configuration
+    pub fn step(&mut self) {
+        if self.status != ComputationStatus::Executing {
+            return;
+        }
+
+        if let Some(status) = self.clock.tick() {
+            self.status = status;
+            return;
+        }
+
+        let transition = &self.machine.view_transitions()[self.current_state][self.grid.get(self.head)];
+        self.current_state = transition.next_state_id();
+        self.grid.set(self.head, transition.write_symbol_id());
+
+        match transition.movement() {
+            Movement::Stay => {},
+            Movement::Absolute(heading) => self.heading = heading,
+            Movement::Relative(quarters) => self.heading = self.heading.turn(quarters)
+        }
+        if transition.movement() != Movement::Stay {
+            let (dx, dy) = self.heading.offset();
+            self.head = (self.head.0 + dx, self.head.1 + dy);
+            self.grid.visit(self.head);
+        }
+
+        let area = self.grid.bounds().area();
+        if area > self.clock.space {
+            if let Some(status) = self.clock.occupy(area) {
+                self.status = status;
+                return;
+            }
+        }
+
+        if self.current_state == self.machine.view_accept_state() {
+            self.status = ComputationStatus::Accept;
+        } else if self.current_state == self.machine.view_reject_state() {
+            self.status = ComputationStatus::Reject;
+        }
+    }
+
+    /// Run the Computation until it halts
+    pub fn run(&mut self) {
+        while self.status == ComputationStatus::Executing {
+            self.step();
+        }
+    }
+}
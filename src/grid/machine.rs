@@ -0,0 +1,107 @@
+use crate::errors::TmError;
+use crate::util::{ State, Symbol };
+use super::computation::GridComputation;
+use super::store::SparseGrid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heading {
+    North,
+    East,
+    South,
+    West
+}
+
+impl Heading {
+    /// The heading after turning clockwise by `quarters` quarter turns
+    pub fn turn(self, quarters: i32) -> Self {
+        let headings = [Heading::North, Heading::East, Heading::South, Heading::West];
+        headings[(self as i32 + quarters).rem_euclid(4) as usize]
+    }
+
+    /// The change in `(x, y)` of one step forward
+    pub fn offset(self) -> (i64, i64) {
+        match self {
+            Heading::North => (0, -1),
+            Heading::East => (1, 0),
+            Heading::South => (0, 1),
+            Heading::West => (-1, 0)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement {
+    /// Leave the head and heading unchanged
+    Stay,
+    /// Face the heading and step one cell forward
+    Absolute(Heading),
+    /// Turn clockwise by a number of quarter turns (negative turns anticlockwise) and step one cell forward
+    Relative(i32)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridTransition(usize, usize, Movement);
+
+impl GridTransition {
+    pub fn new_transition(next_state: usize, write_symbol: usize, movement: Movement) -> Self {
+        GridTransition(next_state, write_symbol, movement)
+    }
+
+    pub fn next_state_id(&self) -> usize { self.0 }
+    pub fn write_symbol_id(&self) -> usize { self.1 }
+    pub fn movement(&self) -> Movement { self.2 }
+}
+
+/// A Turing machine on an unbounded grid, with the symbol `0` as the blank
+pub struct GridMachine {
+    states: Vec<State>,
+    symbols: Vec<Symbol>,
+    transitions: Vec<Vec<GridTransition>>,
+    start_state: usize,
+    accept_state: usize,
+    reject_state: usize
+}
+
+impl GridMachine {
+    pub fn new(states: Vec<State>, symbols: Vec<Symbol>, transitions: Vec<Vec<GridTransition>>, start_state: usize, accept_state: usize, reject_state: usize) -> Self {
+        Self { states, symbols, transitions, start_state, accept_state, reject_state }
+    }
+
+    /// Construct a bounded computation on a blank grid
+    pub fn compute(&self, limits: (Option<usize>, Option<usize>)) -> Result<GridComputation<'_>, TmError> {
+        GridComputation::start(self, SparseGrid::default(), limits)
+    }
+
+    /// Construct a bounded computation on a grid with initial contents
+    pub fn compute_on_grid(&self, grid: SparseGrid, limits: (Option<usize>, Option<usize>)) -> Result<GridComputation<'_>, TmError> {
+        GridComputation::start(self, grid, limits)
+    }
+
+    pub fn view_states(&self) -> &Vec<State> { &self.states }
+    pub fn view_symbols(&self) -> &Vec<Symbol> { &self.symbols }
+    pub fn view_transitions(&self) -> &Vec<Vec<GridTransition>> { &self.transitions }
+    pub fn view_start_state(&self) -> usize { self.start_state }
+    pub fn view_accept_state(&self) -> usize { self.accept_state }
+    pub fn view_reject_state(&self) -> usize { self.reject_state }
+}
+
+/// Langton's ant: on a blank cell turn right, on a filled cell turn left, then flip the cell and step forward
+pub fn langtons_ant() -> GridMachine {
+    GridMachine::new(
+        vec![
+            State::new_state("ant".to_string()),
+            State::new_state("accept".to_string()),
+            State::new_state("reject".to_string()),
+        ],
+        vec![ Symbol::new_symbol('.'), Symbol::new_symbol('#') ],
+        vec![
+            vec![
+                GridTransition::new_transition(0, 1, Movement::Relative(1)),
+                GridTransition::new_transition(0, 0, Movement::Relative(-1)),
+            ],
+            vec![], // accept transitions
+            vec![], // reject transitions
+        ],
+        0, 1, 2
+    )
+}
@@ -0,0 +1,16 @@
+//! # Two-Dimensional Turing Machines
+//!
+//! Machines whose tape is an unbounded grid of cells. Every cell starts blank (symbol `0`) and the
+//! head starts at `(0, 0)` facing north, with `x` growing to the east and `y` growing to the south
+//! so that rows render top to bottom.
+//!
+//! A transition either moves the head in an absolute direction or turns relative to the current
+//! heading and steps forward, which is how turmites such as Langton's ant are described.
+
+pub mod store;
+pub mod machine;
+pub mod computation;
+
+pub use crate::grid::store::{ SparseGrid, Bounds };
+pub use crate::grid::machine::{ GridMachine, GridTransition, Heading, Movement, langtons_ant };
+pub use crate::grid::computation::GridComputation;
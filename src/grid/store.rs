@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+/// The smallest rectangle containing every cell the head has visited or written, inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min_x: i64,
+    pub min_y: i64,
+    pub max_x: i64,
+    pub max_y: i64
+}
+
+impl Bounds {
+    pub fn width(&self) -> usize { (self.max_x - self.min_x + 1) as usize }
+    pub fn height(&self) -> usize { (self.max_y - self.min_y + 1) as usize }
+    pub fn area(&self) -> usize { self.width() * self.height() }
+
+    fn include(&mut self, (x, y): (i64, i64)) {
+        self.min_x = self.min_x.min(x);
+        self.min_y = self.min_y.min(y);
+        self.max_x = self.max_x.max(x);
+        self.max_y = self.max_y.max(y);
+    }
+}
+
+/// A grid that only stores the cells holding a non-blank symbol, and the bounds of the cells used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid {
+    cells: HashMap<(i64, i64), usize>,
+    bounds: Bounds
+}
+
+impl Default for SparseGrid {
+    fn default() -> Self {
+        Self { cells: HashMap::new(), bounds: Bounds { min_x: 0, min_y: 0, max_x: 0, max_y: 0 } }
+    }
+}
+
+impl SparseGrid {
+    /// Load rows of symbol ids with the first symbol of the first row at `(0, 0)`
+    pub fn load(rows: &[Vec<usize>]) -> Self {
+        let mut grid = SparseGrid::default();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, symbol) in row.iter().enumerate() {
+                grid.set((x as i64, y as i64), *symbol);
+            }
+        }
+        grid
+    }
+
+    /// Read the symbol at a cell, blank if it was never written
+    pub fn get(&self, cell: (i64, i64)) -> usize {
+        self.cells.get(&cell).copied().unwrap_or(0)
+    }
+
+    /// Write a symbol to a cell, growing the bounds to include it
+    pub fn set(&mut self, cell: (i64, i64), symbol: usize) {
+        if symbol == 0 {
+            self.cells.remove(&cell);
+        } else {
+            self.cells.insert(cell, symbol);
+        }
+        self.bounds.include(cell);
+    }
+
+    /// Grow the bounds to include a cell without writing to it
+    pub fn visit(&mut self, cell: (i64, i64)) {
+        self.bounds.include(cell);
+    }
+
+    pub fn bounds(&self) -> Bounds { self.bounds }
+
+    /// The number of cells holding a non-blank symbol
+    pub fn count(&self) -> usize { self.cells.len() }
+
+    /// The symbol ids inside the bounds, row by row
+    pub fn rows(&self) -> Vec<Vec<usize>> {
+        (self.bounds.min_y..=self.bounds.max_y)
+            .map(|y| (self.bounds.min_x..=self.bounds.max_x).map(|x| self.get((x, y))).collect())
+            .collect()
+    }
+}
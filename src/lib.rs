@@ -11,10 +11,18 @@
 pub mod errors;
 pub mod deterministic;
 pub mod util;
+pub mod tape;
+pub mod pushdown;
+pub mod grid;
+pub mod probabilistic;
+pub mod alternating;
 
 #[cfg(test)]
+// The first tests were written against `&String` parameters and are kept as they were
+#[allow(clippy::unnecessary_to_owned, clippy::let_unit_value, clippy::single_match)]
 mod tests {
     use crate::util::ComputationStatus;
+    use crate::tape::{ Tape, DenseTape, DequeTape, SparseTape, PackedTape, TwoWayTape };
 
     use super::deterministic::machine::TuringMachine;
     use super::deterministic::computation::Computation;
@@ -278,4 +286,1765 @@ mod tests {
         assert_eq!(timeout_computation.view_clock().time, 5);
         assert_eq!(timeout_computation.view_clock().space, 6);
     }
-}
\ No newline at end of file
+
+    fn last_symbol_machine() -> TuringMachine {
+        let states = vec![
+            State::new_state("start".to_string()),
+            State::new_state("accept".to_string()),
+            State::new_state("reject".to_string()),
+            State::new_state("iterate_to_end".to_string()),
+            State::new_state("last_symbol_check".to_string()),
+        ];
+
+        let language_symbols = vec![ Symbol::new_symbol('0'), Symbol::new_symbol('1') ];
+        let tape_symbols = vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ];
+
+        let transitions = vec![
+            vec![
+                Transition::new_transition(3, 0, 1),
+                Transition::new_transition(3, 1, 1),
+                Transition::new_transition(3, 2, 1),
+                Transition::new_transition(3, 3, 1),
+            ],
+            vec![], // accept transitions
+            vec![], // reject transitions
+            vec![
+                Transition::new_transition(4, 0, -1),
+                Transition::new_transition(3, 1, 1),
+                Transition::new_transition(3, 2, 1),
+                Transition::new_transition(3, 3, 1),
+            ],
+            vec![
+                Transition::new_transition(2, 0, 1),
+                Transition::new_transition(2, 1, 1),
+                Transition::new_transition(1, 2, 1), // accept if last symbol is 0
+                Transition::new_transition(2, 3, 1), // reject if last symbol is 1
+            ]
+        ];
+
+        TuringMachine::new(states, language_symbols, tape_symbols, transitions, 0, 1, 2)
+    }
+
+    fn run_on_tape<T: Tape>(machine: &TuringMachine, word: &str) -> (ComputationStatus, Vec<usize>, usize, usize) {
+        let mut computation = match machine.compute_on_tape::<T>(word, (None, None)) {
+            Ok(computation) => computation,
+            Err(e) => panic!("Test failed with message: {}", e.message)
+        };
+        computation.run();
+
+        (*computation.view_status(), computation.view_tape().to_vec(), computation.view_head_position(), computation.view_clock().time)
+    }
+
+    #[test]
+    fn tapes_agree_on_last_symbol() {
+        let machine = last_symbol_machine();
+
+        for word in ["", "0", "1", "1110", "0001", "0101010"] {
+            let dense = run_on_tape::<DenseTape>(&machine, word);
+            assert_eq!(run_on_tape::<DequeTape>(&machine, word), dense);
+            assert_eq!(run_on_tape::<SparseTape>(&machine, word), dense);
+            assert_eq!(run_on_tape::<PackedTape>(&machine, word), dense);
+        }
+
+        // Moving left off the first cell leaves the head on the start marker
+        let mut b = crate::deterministic::builder::Builder::new(&['_', '>'], &['0', '1']);
+        b.rule("start", ">", None, -3, "back");
+        b.rule("back", ">", None, 1, "check");
+        b.rule("check", "0", None, 0, "accept");
+        let machine = b.build();
+
+        for word in ["", "0", "1"] {
+            let dense = run_on_tape::<DenseTape>(&machine, word);
+            assert_eq!(dense.2, 1);
+            assert_eq!(run_on_tape::<DequeTape>(&machine, word), dense);
+            assert_eq!(run_on_tape::<SparseTape>(&machine, word), dense);
+            assert_eq!(run_on_tape::<PackedTape>(&machine, word), dense);
+        }
+    }
+
+    #[test]
+    fn tape_storage_behaviour() {
+        let mut deque = DequeTape::load(&[1, 2], 4).unwrap();
+        assert_eq!(deque.move_head(-2), 0);
+        assert_eq!(deque, vec![1, 2]);
+        assert_eq!(deque.head(), 0);
+
+        let mut two_way = TwoWayTape::load(&[1, 2], 4).unwrap();
+        assert_eq!(two_way.move_head(-2), 2);
+        assert_eq!(two_way, vec![0, 0, 1, 2]);
+        assert_eq!(two_way.head(), 0);
+
+        let mut sparse = SparseTape::load(&[1, 2], 4).unwrap();
+        assert_eq!(sparse.move_head(1_000_000), 999_999);
+        assert_eq!(sparse.len(), 1_000_001);
+        sparse.write(3);
+        assert_eq!(sparse.window(999_999, 1_000_001).collect::<Vec<usize>>(), vec![0, 3]);
+
+        let mut packed = PackedTape::load(&[1, 3, 2], 4).unwrap();
+        assert_eq!(packed.view_bits(), 2);
+        assert_eq!(packed.move_head(40), 38);
+        packed.write(3);
+        assert_eq!(packed.get(40), 3);
+        assert_eq!(packed.window(0, 3).collect::<Vec<usize>>(), vec![1, 3, 2]);
+        assert!(PackedTape::load(&[4], 4).is_err());
+    }
+
+    #[test]
+    fn compiled_matches_interpreter() {
+        let machine = last_symbol_machine();
+        let compiled = match machine.compile() {
+            Ok(compiled) => compiled,
+            Err(e) => panic!("Test failed with message: {}", e.message)
+        };
+
+        for word in ["", "0", "1", "1110", "0001", "0101010"] {
+            for limits in [(None, None), (Some(3), None), (None, Some(4))] {
+                let mut interpreted = machine.bounded_compute(word, limits).unwrap();
+                let mut fast = compiled.bounded_compute(word, limits).unwrap();
+                interpreted.run();
+                fast.run();
+
+                assert_eq!(fast.view_status(), interpreted.view_status());
+                assert_eq!(fast.view_tape(), interpreted.view_tape());
+                assert_eq!(fast.view_head_position(), interpreted.view_head_position());
+                assert_eq!(fast.view_current_state(), interpreted.view_current_state());
+                assert_eq!(fast.view_clock().time, interpreted.view_clock().time);
+                assert_eq!(fast.view_clock().space, interpreted.view_clock().space);
+            }
+        }
+    }
+
+    #[test]
+    fn compile_rejects_long_moves() {
+        let machine = TuringMachine::new(
+            vec![ State::new_state("q0".to_string()), State::new_state("q1".to_string()), State::new_state("q2".to_string()) ],
+            vec![ Symbol::new_symbol('0') ],
+            vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ],
+            vec![ vec![ Transition::new_transition(1, 0, 1000), Transition::new_transition(1, 1, 1), Transition::new_transition(1, 2, 1) ] ],
+            0, 1, 2
+        );
+
+        assert!(machine.compile().err().unwrap().message.contains("moves too far"));
+    }
+
+    #[test]
+    fn compile_rejects_missing_transitions() {
+        let states = || vec![ State::new_state("q0".to_string()), State::new_state("q1".to_string()), State::new_state("q2".to_string()) ];
+        let symbols = || (vec![ Symbol::new_symbol('0') ], vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ]);
+
+        let (language, tape) = symbols();
+        let short = TuringMachine::new(states(), language, tape, vec![ vec![ Transition::new_transition(1, 0, 1) ] ], 0, 1, 2);
+        assert!(short.compile().err().unwrap().message.contains("has 1 transitions for 3 symbols"));
+
+        let (language, tape) = symbols();
+        let empty = TuringMachine::new(states(), language, tape, vec![], 0, 1, 2);
+        assert!(empty.compile().is_err());
+
+        let (language, tape) = symbols();
+        let halting = TuringMachine::new(states(), language, tape, vec![], 1, 1, 2);
+        assert!(halting.compile().is_err());
+    }
+
+    type Limits = (Option<usize>, Option<usize>);
+
+    /// Compile generated source with `compiler` and run it on each word, or `None` when the compiler
+    /// cannot be started
+    fn run_generated(source: &str, name: &str, compiler: &str, args: &[&str], runs: &[Vec<String>]) -> Option<Vec<String>> {
+        let directory = std::env::temp_dir().join(format!("tm-engine-codegen-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&directory).unwrap();
+        let source_path = directory.join(name);
+        let binary_path = directory.join("machine");
+        std::fs::write(&source_path, source).unwrap();
+
+        let compiled = std::process::Command::new(compiler)
+            .args(args)
+            .arg("-o").arg(&binary_path)
+            .arg(&source_path)
+            .output();
+
+        match compiled {
+            Ok(output) => assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr)),
+            Err(_) => {
+                let _ = std::fs::remove_dir_all(&directory);
+                return None;
+            }
+        }
+
+        let outputs = runs.iter().map(|run| {
+            let output = std::process::Command::new(&binary_path).args(run).output().unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()
+        }).collect();
+
+        let _ = std::fs::remove_dir_all(&directory);
+        Some(outputs)
+    }
+
+    fn describe_run(machine: &TuringMachine, word: &str, limits: Limits) -> String {
+        let mut computation = machine.bounded_compute(word, limits).unwrap();
+        computation.run();
+
+        let cells: Vec<String> = computation.view_tape().to_vec().iter().map(|cell| cell.to_string()).collect();
+        format!("status {:?}\ntime {}\nspace {}\nhead {}\ntape {}\n",
+            computation.view_status(), computation.view_clock().time, computation.view_clock().space,
+            computation.view_head_position(), cells.join(" "))
+    }
+
+    fn codegen_cases(words: &[&str]) -> Vec<(String, Limits)> {
+        let mut cases = vec![];
+        for word in words {
+            cases.push((word.to_string(), (None, None)));
+        }
+        let last = words[words.len() - 1].to_string();
+        cases.push((last.clone(), (Some(3), None)));
+        cases.push((last, (None, Some(5))));
+        cases
+    }
+
+    /// Machines for the generated code to match, with the words to run them on
+    fn codegen_machines() -> Vec<(TuringMachine, Vec<&'static str>)> {
+        use crate::deterministic::builder::Builder;
+
+        // Leaps three cells at a time, growing the tape well past the word
+        let mut b = Builder::new(&['_', '>', 'x'], &['0', '1']);
+        b.rule("start", ">", None, 1, "leap");
+        b.rule("leap", "01", Some('x'), 3, "leap");
+        b.rule("leap", "_", Some('x'), -2, "land");
+        b.rule("land", "01_>", None, 0, "accept");
+        let leaping = b.build();
+
+        vec![
+            (last_symbol_machine(), vec!["", "0", "1", "1110", "0001", "0101010"]),
+            (crate::deterministic::catalogue::binary_addition(), vec!["0+0", "1+1", "101+11", "11+0", "+", "1101"]),
+            (leaping, vec!["", "0", "01", "0110", "1111111"])
+        ]
+    }
+
+    fn codegen_args(cases: &[(String, Limits)]) -> Vec<Vec<String>> {
+        cases.iter().map(|(word, limits)| vec![
+            word.clone(),
+            limits.0.map_or(usize::MAX.to_string(), |limit| limit.to_string()),
+            limits.1.map_or(usize::MAX.to_string(), |limit| limit.to_string()),
+        ]).collect()
+    }
+
+    #[test]
+    fn generated_rust_matches_computation() {
+        let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+
+        for (machine, words) in codegen_machines() {
+            let source = crate::deterministic::codegen::to_rust(&machine).unwrap();
+            let cases = codegen_cases(&words);
+
+            let outputs = run_generated(&source, "machine.rs", &rustc, &["--edition", "2021"], &codegen_args(&cases))
+                .unwrap_or_else(|| panic!("Cannot start {} to compile the generated source.", rustc));
+            for ((word, limits), output) in cases.iter().zip(outputs) {
+                assert_eq!(output, describe_run(&machine, word, *limits), "on word {:?}", word);
+            }
+        }
+    }
+
+    #[test]
+    fn generated_c_matches_computation() {
+        for (machine, words) in codegen_machines() {
+            let source = crate::deterministic::codegen::to_c(&machine).unwrap();
+            let cases = codegen_cases(&words);
+
+            // Skipped where no C compiler is installed
+            let Some(outputs) = run_generated(&source, "machine.c", "cc", &["-std=c99"], &codegen_args(&cases)) else {
+                return;
+            };
+            for ((word, limits), output) in cases.iter().zip(outputs) {
+                assert_eq!(output, describe_run(&machine, word, *limits), "on word {:?}", word);
+            }
+        }
+    }
+
+    #[test]
+    fn universal_encoding_round_trip() {
+        let machine = last_symbol_machine();
+        let encoding = crate::deterministic::universal::encode(&machine, "0110").unwrap();
+        let (decoded, word) = crate::deterministic::universal::decode(&encoding).unwrap();
+
+        assert_eq!(word, "0110");
+        assert_eq!(decoded.view_transitions(), machine.view_transitions());
+        assert_eq!(decoded.view_tape_symbols(), machine.view_tape_symbols());
+        assert_eq!(decoded.view_language_symbols(), machine.view_language_symbols());
+        assert_eq!(decoded.view_states().len(), machine.view_states().len());
+        assert_eq!(decoded.view_start_state(), machine.view_start_state());
+        assert_eq!(decoded.view_accept_state(), machine.view_accept_state());
+        assert_eq!(decoded.view_reject_state(), machine.view_reject_state());
+        assert_eq!(crate::deterministic::universal::encode(&decoded, &word).unwrap(), encoding);
+
+        assert!(crate::deterministic::universal::decode("0#;1#0").is_err());
+        assert!(crate::deterministic::universal::encode(&machine, "2").is_err());
+
+        // Five states take 3 bits and four symbols 2 bits
+        let sections: Vec<&str> = encoding.split('#').collect();
+        let header: Vec<&str> = sections[0].split(',').collect();
+        let replace_header = |index: usize, value: &str| {
+            let mut header = header.clone();
+            header[index] = value;
+            [header.join(",").as_str(), sections[1], sections[2], sections[3]].join("#")
+        };
+        let malformed = [
+            [sections[0], sections[1], "00é0", sections[3]].join("#"),
+            [sections[0], sections[1], "11101", sections[3]].join("#"),
+            [sections[0], "", sections[2], sections[3]].join("#"),
+            replace_header(header.len() - 2, "111"),
+            replace_header(header.len() - 1, "101")
+        ];
+        for encoding in malformed {
+            assert!(crate::deterministic::universal::decode(&encoding).is_err(), "for {}", encoding);
+        }
+    }
+
+    #[test]
+    fn universal_machine_agrees_with_simulation() {
+        let machine = last_symbol_machine();
+        let universal = crate::deterministic::universal::universal_machine();
+
+        for word in ["", "0", "1", "10", "01", "1110", "0001"] {
+            let mut direct = machine.compute(word).unwrap();
+            direct.run();
+
+            let encoding = crate::deterministic::universal::encode(&machine, word).unwrap();
+            let mut simulated = universal.bounded_compute(&encoding, (Some(10_000_000), None)).unwrap();
+            simulated.run();
+
+            assert_eq!(simulated.view_status(), direct.view_status(), "on word {:?}", word);
+        }
+    }
+
+    #[test]
+    fn builder_missing_transitions() {
+        use crate::deterministic::builder::Builder;
+
+        let mut b = Builder::new(&['_', '>'], &['0']);
+        b.rule("start", ">", None, 1, "accept");
+        assert_eq!(b.build().view_transitions()[0][2], Transition::new_transition(2, 2, 0));
+
+        let mut b = Builder::new(&['_', '>'], &['0']);
+        b.missing_direction(1);
+        b.rule("start", ">", None, 1, "accept");
+        assert_eq!(b.build().view_transitions()[0][2], Transition::new_transition(2, 2, 1));
+
+        // The universal machine keeps moving right into its reject state
+        let universal = crate::deterministic::universal::universal_machine();
+        for (read, transition) in universal.view_transitions()[0].iter().enumerate().filter(|(read, _)| *read != 1) {
+            assert_eq!(transition, &Transition::new_transition(2, read, 1));
+        }
+    }
+
+    /// Every word over `alphabet` of length at most `max_length`
+    fn all_words(alphabet: &[char], max_length: usize) -> Vec<String> {
+        let mut words = vec![String::new()];
+        let mut previous = vec![String::new()];
+
+        for _ in 0..max_length {
+            previous = previous.iter()
+                .flat_map(|word| alphabet.iter().map(move |c| format!("{word}{c}")))
+                .collect();
+            words.extend(previous.iter().cloned());
+        }
+
+        words
+    }
+
+    fn run_word<'a>(machine: &'a TuringMachine, word: &str) -> Computation<'a> {
+        let mut computation = match machine.bounded_compute(word, (Some(100_000), None)) {
+            Ok(computation) => computation,
+            Err(e) => panic!("Test failed with message: {}", e.message)
+        };
+        computation.run();
+        computation
+    }
+
+    /// Accepts when the first symbol of the word is 0
+    fn first_symbol_machine() -> TuringMachine {
+        let states = vec![
+            State::new_state("start".to_string()),
+            State::new_state("accept".to_string()),
+            State::new_state("reject".to_string()),
+            State::new_state("first_symbol_check".to_string()),
+        ];
+
+        let language_symbols = vec![ Symbol::new_symbol('0'), Symbol::new_symbol('1') ];
+        let tape_symbols = vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ];
+
+        let transitions = vec![
+            vec![
+                Transition::new_transition(2, 0, 1),
+                Transition::new_transition(3, 1, 1),
+                Transition::new_transition(2, 2, 1),
+                Transition::new_transition(2, 3, 1),
+            ],
+            vec![], // accept transitions
+            vec![], // reject transitions
+            vec![
+                Transition::new_transition(2, 0, 1),
+                Transition::new_transition(2, 1, 1),
+                Transition::new_transition(1, 2, 1), // accept if first symbol is 0
+                Transition::new_transition(2, 3, 1),
+            ],
+        ];
+
+        TuringMachine::new(states, language_symbols, tape_symbols, transitions, 0, 1, 2)
+    }
+
+    #[test]
+    fn sequence_and_branch_composition() {
+        use crate::deterministic::compose::{ sequence, branch };
+
+        let both = sequence(&first_symbol_machine(), &last_symbol_machine()).unwrap();
+        let either = branch(&first_symbol_machine(), &last_symbol_machine(), &TuringMachine::default()).unwrap();
+
+        assert_eq!(both.view_states()[both.view_start_state()], State::new_state("first.start".to_string()));
+
+        for word in all_words(&['0', '1'], 5) {
+            let starts_and_ends_with_zero = word.len() >= 2 && word.starts_with('0') && word.ends_with('0');
+
+            let expected = if starts_and_ends_with_zero { ComputationStatus::Accept } else { ComputationStatus::Reject };
+            assert_eq!(run_word(&both, &word).view_status(), &expected, "sequence on {:?}", word);
+
+            let expected = if starts_and_ends_with_zero || !word.starts_with('0') { ComputationStatus::Accept } else { ComputationStatus::Reject };
+            assert_eq!(run_word(&either, &word).view_status(), &expected, "branch on {:?}", word);
+        }
+    }
+
+    #[test]
+    fn repeat_composition_merges_alphabets() {
+        // Steps over the start marker and every a, rejecting on anything else
+        let step_over_a = TuringMachine::new(
+            vec![ State::new_state("q0".to_string()), State::new_state("q1".to_string()), State::new_state("q2".to_string()) ],
+            vec![ Symbol::new_symbol('a') ],
+            vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ],
+            vec![
+                vec![
+                    Transition::new_transition(2, 0, 0),
+                    Transition::new_transition(1, 1, 1),
+                    Transition::new_transition(1, 2, 1),
+                ],
+                vec![],
+                vec![],
+            ],
+            0, 1, 2
+        );
+
+        let looped = crate::deterministic::compose::repeat(&step_over_a).unwrap();
+        let computation = run_word(&looped, "aaa");
+        assert_eq!(computation.view_status(), &ComputationStatus::Accept);
+        assert_eq!(computation.view_head_position(), 4);
+        assert_eq!(computation.view_clock().time, 5);
+
+        assert_eq!(run_word(&looped, "").view_head_position(), 1);
+
+        let combined = crate::deterministic::compose::sequence(&step_over_a, &last_symbol_machine()).unwrap();
+        let language: Vec<char> = combined.view_language_symbols().iter().map(|symbol| symbol.as_char()).collect();
+        assert_eq!(language, vec!['a', '0', '1']);
+
+        // The last symbol machine starts one cell further right, and rejects the a it does not know
+        assert_eq!(run_word(&combined, "10").view_status(), &ComputationStatus::Accept);
+        assert_eq!(run_word(&combined, "01").view_status(), &ComputationStatus::Reject);
+        assert_eq!(run_word(&combined, "a0").view_status(), &ComputationStatus::Reject);
+
+        let missing_start = TuringMachine::new(
+            vec![ State::new_state("q0".to_string()), State::new_state("q1".to_string()), State::new_state("q2".to_string()) ],
+            vec![ Symbol::new_symbol('a') ],
+            vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ],
+            vec![ vec![], vec![], vec![] ],
+            5, 1, 2
+        );
+        assert!(crate::deterministic::compose::sequence(&step_over_a, &missing_start).err().unwrap().message.contains("missing state 5"));
+        assert!(crate::deterministic::compose::repeat(&missing_start).is_err());
+    }
+
+    /// The used extent of the tape as characters, without trailing blanks
+    fn tape_string(computation: &Computation) -> String {
+        let machine = computation.view_machine();
+        let symbols: Vec<char> = machine.view_tape_symbols().iter()
+            .chain(machine.view_language_symbols().iter())
+            .map(|symbol| symbol.as_char())
+            .collect();
+
+        let tape: String = computation.view_tape().to_vec().iter().map(|id| symbols[*id]).collect();
+        tape.trim_end_matches('_').to_string()
+    }
+
+    #[test]
+    fn library_moves_and_shifts() {
+        use crate::deterministic::library::{ move_to_right_end, move_to_start, shift_right, erase };
+        let language = ['a', 'b', 'c'];
+
+        let to_end = move_to_right_end(&language).unwrap();
+        let computation = run_word(&to_end, "abca");
+        assert_eq!(computation.view_status(), &ComputationStatus::Accept);
+        assert_eq!(computation.view_head_position(), 5);
+
+        let there_and_back = crate::deterministic::compose::sequence(&to_end, &move_to_start(&language).unwrap()).unwrap();
+        let computation = run_word(&there_and_back, "abca");
+        assert_eq!(computation.view_status(), &ComputationStatus::Accept);
+        assert_eq!(computation.view_head_position(), 0);
+
+        let shift = shift_right(&language).unwrap();
+        let computation = run_word(&shift, "abc");
+        assert_eq!(computation.view_status(), &ComputationStatus::Accept);
+        assert_eq!(tape_string(&computation), ">_abc");
+        assert_eq!(computation.view_head_position(), 0);
+
+        let erase = erase(&language).unwrap();
+        let computation = run_word(&erase, "abc");
+        assert_eq!(computation.view_status(), &ComputationStatus::Accept);
+        assert_eq!(tape_string(&computation), ">");
+        assert_eq!(computation.view_head_position(), 0);
+
+        assert!(shift_right(&['a', '_']).is_err());
+        assert!(shift_right(&['a', 'a']).is_err());
+    }
+
+    #[test]
+    fn library_copies_and_compares_blocks() {
+        use crate::deterministic::library::{ copy_block, compare_blocks };
+        let language = ['0', '1'];
+
+        let copy = copy_block(&language, '#').unwrap();
+        let computation = run_word(&copy, "0110");
+        assert_eq!(computation.view_status(), &ComputationStatus::Accept);
+        assert_eq!(tape_string(&computation), ">0110#0110");
+        assert_eq!(computation.view_head_position(), 0);
+
+        let language = ['0', '1', '#'];
+        let compare = compare_blocks(&language, '#').unwrap();
+        for (word, expected) in [("01#01", true), ("#", true), ("01#0", false), ("01#011", false), ("0#1", false), ("1#1#", false)] {
+            let computation = run_word(&compare, word);
+            let status = if expected { ComputationStatus::Accept } else { ComputationStatus::Reject };
+            assert_eq!(computation.view_status(), &status, "on word {:?}", word);
+            assert_eq!(tape_string(&computation), format!(">{word}"));
+            assert_eq!(computation.view_head_position(), 0);
+        }
+
+        let copy_then_compare = crate::deterministic::compose::sequence(
+            &copy_block(&language, '#').unwrap(), &compare
+        ).unwrap();
+        for word in all_words(&['0', '1'], 4) {
+            assert_eq!(run_word(&copy_then_compare, &word).view_status(), &ComputationStatus::Accept);
+        }
+    }
+
+    #[test]
+    fn library_increments_binary() {
+        let increment = crate::deterministic::library::increment_binary(&['0', '1'], '0', '1').unwrap();
+
+        assert_eq!(tape_string(&run_word(&increment, "")), ">1");
+        for n in 0..40 {
+            let computation = run_word(&increment, &format!("{:b}", n));
+            assert_eq!(computation.view_status(), &ComputationStatus::Accept);
+            assert_eq!(tape_string(&computation), format!(">{:b}", n + 1));
+            assert_eq!(computation.view_head_position(), 0);
+        }
+
+        assert!(crate::deterministic::library::increment_binary(&['0'], '0', '1').is_err());
+    }
+
+    /// Run every word and compare the status, step count and tape against the expected values
+    fn check_runs(machine: &TuringMachine, runs: &[(&str, ComputationStatus, usize, &str)]) {
+        for (word, status, time, tape) in runs {
+            let computation = run_word(machine, word);
+            assert_eq!(computation.view_status(), status, "on word {:?}", word);
+            assert_eq!(computation.view_clock().time, *time, "on word {:?}", word);
+            assert_eq!(tape_string(&computation), *tape, "on word {:?}", word);
+        }
+    }
+
+    /// Check that the machine decides exactly the words satisfying `member` up to `max_length`
+    fn check_decides(machine: &TuringMachine, alphabet: &[char], max_length: usize, member: impl Fn(&str) -> bool) {
+        for word in all_words(alphabet, max_length) {
+            let expected = if member(&word) { ComputationStatus::Accept } else { ComputationStatus::Reject };
+            assert_eq!(run_word(machine, &word).view_status(), &expected, "on word {:?}", word);
+        }
+    }
+
+    #[test]
+    fn catalogue_deciders() {
+        use crate::deterministic::catalogue::{ powers_of_two, w_hash_w, a_n_b_n_c_n, palindromes };
+        use ComputationStatus::{ Accept, Reject };
+
+        check_runs(&powers_of_two(), &[
+            ("", Reject, 2, ">"),
+            ("0", Accept, 3, ">"),
+            ("0000", Accept, 22, ">_xxx"),
+            ("000000", Reject, 20, ">_xxx0x"),
+            ("00000000", Accept, 58, ">_xxxxxxx"),
+        ]);
+        check_decides(&powers_of_two(), &['0'], 17, |w| w.len().is_power_of_two());
+
+        check_runs(&w_hash_w(), &[
+            ("#", Accept, 3, ">#"),
+            ("01#01", Accept, 19, ">xx#xx"),
+            ("011#011", Accept, 33, ">xxx#xxx"),
+            ("01#10", Reject, 5, ">x1#10"),
+        ]);
+        check_decides(&w_hash_w(), &['0', '1', '#'], 6, |w| {
+            let parts: Vec<&str> = w.split('#').collect();
+            parts.len() == 2 && parts[0] == parts[1]
+        });
+
+        check_runs(&a_n_b_n_c_n(), &[
+            ("", Accept, 2, ">"),
+            ("aabbcc", Accept, 24, ">xxyyzz"),
+            ("aaabbbccc", Accept, 47, ">xxxyyyzzz"),
+            ("abcabc", Reject, 9, ">xyzabc"),
+        ]);
+        check_decides(&a_n_b_n_c_n(), &['a', 'b', 'c'], 7, |w| {
+            let n = w.len() / 3;
+            *w == format!("{}{}{}", "a".repeat(n), "b".repeat(n), "c".repeat(n))
+        });
+
+        check_runs(&palindromes(), &[
+            ("", Accept, 2, ">"),
+            ("0110", Accept, 16, ">xxxx"),
+            ("01010", Accept, 22, ">xxxxx"),
+            ("0111", Reject, 7, ">x111"),
+        ]);
+        check_decides(&palindromes(), &['0', '1'], 8, |w| w.chars().rev().collect::<String>() == w);
+    }
+
+    #[test]
+    fn catalogue_arithmetic() {
+        use crate::deterministic::catalogue::{ binary_addition, unary_multiplication };
+        use ComputationStatus::Accept;
+
+        check_runs(&binary_addition(), &[
+            ("0+0", Accept, 13, ">0"),
+            ("11+1", Accept, 26, ">100"),
+            ("101+11", Accept, 54, ">1000"),
+            ("1111+1111", Accept, 242, ">11110"),
+        ]);
+        let addition = binary_addition();
+        for a in 0..9 {
+            for b in 0..9 {
+                let computation = run_word(&addition, &format!("{:b}+{:b}", a, b));
+                assert_eq!(tape_string(&computation), format!(">{:b}", a + b));
+            }
+        }
+
+        check_runs(&unary_multiplication(), &[
+            ("*", Accept, 7, ">*="),
+            ("1*1", Accept, 23, ">1*1=1"),
+            ("11*111", Accept, 109, ">11*111=111111"),
+            ("111*11", Accept, 113, ">111*11=111111"),
+        ]);
+        let multiplication = unary_multiplication();
+        for m in 0..5 {
+            for n in 0..5 {
+                let word = format!("{}*{}", "1".repeat(m), "1".repeat(n));
+                let computation = run_word(&multiplication, &word);
+                assert_eq!(tape_string(&computation), format!(">{}={}", word, "1".repeat(m * n)));
+            }
+        }
+    }
+
+    #[test]
+    fn catalogue_busy_beavers() {
+        for (states, steps, ones) in [(2, 6, 4), (3, 21, 5), (4, 107, 13)] {
+            let machine = crate::deterministic::catalogue::busy_beaver(states).unwrap();
+            let mut computation = machine.compute_on_tape::<TwoWayTape>("", (None, None)).unwrap();
+            computation.run();
+
+            assert_eq!(computation.view_status(), &ComputationStatus::Accept);
+            assert_eq!(computation.view_clock().time, steps);
+            assert_eq!(computation.view_tape().to_vec().iter().filter(|id| **id == 2).count(), ones);
+        }
+
+        // On a one-way tape the head stops at the start marker and the run diverges from the champion
+        let machine = crate::deterministic::catalogue::busy_beaver(2).unwrap();
+        let mut computation = machine.compute("").unwrap();
+        computation.run();
+        assert_ne!(computation.view_clock().time, 6);
+
+        assert!(crate::deterministic::catalogue::busy_beaver(6).is_err());
+    }
+
+    const ENDS_IN_ZERO: &str = "
+        // Accept words over 0 and 1 that end in 0
+        language 0 1
+        move right
+        while read 0 1
+            move right
+        end
+        move left
+        if read 0
+            accept
+        end
+        reject
+    ";
+
+    #[test]
+    fn program_compiles_to_machine() {
+        use crate::deterministic::program::compile;
+
+        let program = compile(ENDS_IN_ZERO).unwrap();
+        let compiled = program.view_machine();
+        let reference = last_symbol_machine();
+        for word in all_words(&['0', '1'], 6) {
+            assert_eq!(run_word(compiled, &word).view_status(), run_word(&reference, &word).view_status(), "on word {:?}", word);
+        }
+
+        // Each state maps back to the statement it executes
+        let computation = run_word(compiled, "10");
+        assert_eq!(program.line_of(0), None);
+        assert_eq!(program.line_of(3), Some(4));
+        assert_eq!(program.statement_of(4), Some("while read 0 1"));
+        assert_eq!(computation.view_clock().time, 9);
+
+        let binary = compile("
+            language 0 1
+            tape x
+            move right
+            loop:
+            if read 0
+                write x
+            else
+                if read _
+                    goto done
+                end
+                write 1
+            end
+            move right
+            goto loop
+            done:
+            move left 2
+            accept
+        ").unwrap();
+        check_runs(binary.view_machine(), &[
+            ("", ComputationStatus::Accept, 5, ">"),
+            ("010", ComputationStatus::Accept, 15, ">x1x"),
+        ]);
+    }
+
+    #[test]
+    fn program_compile_errors() {
+        use crate::deterministic::program::compile;
+
+        let error = |source: &str| match compile(source) {
+            Ok(_) => panic!("{:?} compiled", source),
+            Err(e) => e.message
+        };
+
+        assert_eq!(error("move right"), "Line 1: Language must come first.");
+        assert_eq!(error("language 0\nmove up"), "Line 2: Move must be followed by left or right.");
+        assert_eq!(error("language 0\nwhile read 0\nmove right"), "Line 2: While is missing its end.");
+        assert_eq!(error("language 0\nend"), "Line 2: End without a matching if or while.");
+        assert_eq!(error("language 0\n\nwrite 2"), "Line 3: Symbol 2 is not declared.");
+        assert_eq!(error("language 0\ngoto nowhere"), "Line 2: Unknown label nowhere.");
+        assert_eq!(error("language 0\nhere:\ngoto here"), "Line 3: Jumps loop forever without a statement.");
+        assert_eq!(error("language 0 1\ntape 1"), "Symbol 1 is declared more than once.");
+    }
+
+    #[test]
+    fn regular_expressions_to_machines() {
+        use crate::deterministic::regular::{ Dfa, regex_to_machine };
+
+        let ends_in_zero = regex_to_machine("(0|1)*0", &['0', '1']).unwrap();
+        let reference = last_symbol_machine();
+        for word in all_words(&['0', '1'], 6) {
+            let computation = run_word(&ends_in_zero, &word);
+            assert_eq!(computation.view_status(), run_word(&reference, &word).view_status(), "on word {:?}", word);
+            assert_eq!(computation.view_clock().time, word.len() + 2);
+        }
+
+        let pattern = regex_to_machine("a(b|c)*a?|\\.+|()", &['a', 'b', 'c', '.']).unwrap();
+        check_decides(&pattern, &['a', 'b', 'c', '.'], 4, |w| {
+            w.is_empty()
+                || w.chars().all(|c| c == '.')
+                || (w.starts_with('a') && w[1..].trim_end_matches('a').chars().all(|c| c == 'b' || c == 'c') && w[1..].matches('a').count() <= 1)
+        });
+
+        // The tenth symbol from the end is a 1, which needs an exponential number of states
+        let tenth_from_end = Dfa::from_regex(&format!("(0|1)*1{}", ".".repeat(9)), &['0', '1']).unwrap();
+        assert!(tenth_from_end.view_transitions().len() >= 1024);
+        check_decides(&tenth_from_end.to_machine(), &['0', '1'], 12, |w| w.len() >= 10 && w.as_bytes()[w.len() - 10] == b'1');
+
+        for pattern in ["(0", "0)", "*0", "2", "0\\"] {
+            assert!(Dfa::from_regex(pattern, &['0', '1']).is_err(), "{:?} parsed", pattern);
+        }
+    }
+
+    #[test]
+    fn dfa_to_machine() {
+        use crate::deterministic::regular::Dfa;
+
+        // Binary numbers that are multiples of three, tracking the remainder so far
+        let multiple_of_three = Dfa::new(vec!['0', '1'], vec![vec![0, 1], vec![2, 0], vec![1, 2]], 0, &[0]).unwrap();
+        let machine = multiple_of_three.to_machine();
+        for n in 0..64 {
+            let word = format!("{:b}", n);
+            assert_eq!(multiple_of_three.accepts(&word).unwrap(), n % 3 == 0);
+            let expected = if n % 3 == 0 { ComputationStatus::Accept } else { ComputationStatus::Reject };
+            assert_eq!(run_word(&machine, &word).view_status(), &expected, "on {}", n);
+        }
+
+        assert!(Dfa::new(vec!['0'], vec![vec![1]], 0, &[]).is_err());
+        assert!(Dfa::new(vec!['0', '1'], vec![vec![0]], 0, &[]).is_err());
+        assert!(Dfa::new(vec!['_'], vec![vec![0]], 0, &[]).is_err());
+        assert!(Dfa::new(vec!['0'], vec![vec![0]], 0, &[1]).is_err());
+    }
+
+    type PdaRule<'a> = (usize, Option<char>, Option<char>, usize, &'a str);
+
+    fn pda(states: usize, input: &str, stack: &str, rules: &[PdaRule], accept: Vec<usize>) -> crate::pushdown::PDA {
+        use crate::pushdown::automaton::Transition as PdaTransition;
+
+        let input_ids = |c: char| input.chars().position(|x| x == c).unwrap();
+        let stack_ids = |c: char| stack.chars().position(|x| x == c).unwrap();
+        let transitions = rules.iter().map(|(from, read, pop, next, push)| {
+            PdaTransition::new_transition(*from, read.map(input_ids), pop.map(stack_ids), *next, push.chars().map(stack_ids).collect())
+        }).collect();
+
+        crate::pushdown::PDA::new(
+            (0..states).map(|i| State::new_state(format!("q{}", i))).collect(),
+            input.chars().map(Symbol::new_symbol).collect(),
+            stack.chars().map(Symbol::new_symbol).collect(),
+            transitions, 0, accept
+        ).unwrap()
+    }
+
+    /// Sipser's automaton for $\{ 0^n 1^n \mid n \geq 0 \}$
+    fn zeros_then_ones() -> crate::pushdown::PDA {
+        pda(4, "01", "0$", &[
+            (0, None, None, 1, "$"),
+            (1, Some('0'), None, 1, "0"),
+            (1, Some('1'), Some('0'), 2, ""),
+            (2, Some('1'), Some('0'), 2, ""),
+            (2, None, Some('$'), 3, ""),
+        ], vec![0, 3])
+    }
+
+    /// Guesses the middle of a palindrome over 0 and 1
+    fn pda_palindromes() -> crate::pushdown::PDA {
+        pda(4, "01", "01$", &[
+            (0, None, None, 1, "$"),
+            (1, Some('0'), None, 1, "0"),
+            (1, Some('1'), None, 1, "1"),
+            (1, None, None, 2, ""),
+            (1, Some('0'), None, 2, ""),
+            (1, Some('1'), None, 2, ""),
+            (2, Some('0'), Some('0'), 2, ""),
+            (2, Some('1'), Some('1'), 2, ""),
+            (2, None, Some('$'), 3, ""),
+        ], vec![3])
+    }
+
+    fn is_zeros_then_ones(w: &str) -> bool {
+        let n = w.len() / 2;
+        w.len().is_multiple_of(2) && w[..n].chars().all(|c| c == '0') && w[n..].chars().all(|c| c == '1')
+    }
+
+    #[test]
+    fn pushdown_simulation() {
+        for (automaton, member) in [
+            (zeros_then_ones(), &is_zeros_then_ones as &dyn Fn(&str) -> bool),
+            (pda_palindromes(), &|w: &str| w.chars().rev().collect::<String>() == w),
+        ] {
+            for word in all_words(&['0', '1'], 8) {
+                let mut computation = automaton.compute(&word).unwrap();
+                computation.run();
+                let expected = if member(&word) { ComputationStatus::Accept } else { ComputationStatus::Reject };
+                assert_eq!(computation.view_status(), &expected, "on word {:?}", word);
+            }
+        }
+
+        let automaton = zeros_then_ones();
+        let mut computation = automaton.compute("0011").unwrap();
+        computation.run();
+        assert_eq!(computation.view_clock().time, 6);
+        assert_eq!(computation.view_clock().space, 3);
+
+        // An epsilon loop that keeps pushing never dies
+        let pushing = pda(1, "0", "x", &[(0, None, None, 0, "x")], vec![]);
+        let mut computation = pushing.bounded_compute("0", (None, Some(5))).unwrap();
+        computation.run();
+        assert_eq!(computation.view_status(), &ComputationStatus::Spaceout);
+        let mut computation = pushing.bounded_compute("0", (Some(5), None)).unwrap();
+        computation.run();
+        assert_eq!(computation.view_status(), &ComputationStatus::Timeout);
+
+        assert!(zeros_then_ones().compute("2").is_err());
+    }
+
+    #[test]
+    fn pushdown_to_turing_machine() {
+        let machine = zeros_then_ones().to_turing_machine().unwrap();
+        check_decides(&machine, &['0', '1'], 5, is_zeros_then_ones);
+
+        let machine = pda_palindromes().to_turing_machine().unwrap();
+        for word in all_words(&['0', '1'], 3) {
+            let mut computation = machine.bounded_compute(&word, (Some(20_000_000), None)).unwrap();
+            computation.run();
+            let expected = if word.chars().rev().collect::<String>() == word { ComputationStatus::Accept } else { ComputationStatus::Reject };
+            assert_eq!(computation.view_status(), &expected, "on word {:?}", word);
+        }
+
+        // The simulation prunes the repeated configurations of an epsilon self-loop, the machine cannot
+        let looping = pda(1, "0", "x", &[(0, None, None, 0, "")], vec![]);
+        let mut computation = looping.compute("").unwrap();
+        computation.run();
+        assert_eq!(computation.view_status(), &ComputationStatus::Reject);
+        assert!(looping.to_turing_machine().is_err());
+
+        let neutral = pda(2, "0", "x", &[(0, None, None, 1, "x"), (1, None, Some('x'), 0, "")], vec![]);
+        assert!(neutral.to_turing_machine().is_err());
+        let mixed = pda(1, "0", "x", &[(0, None, None, 0, "x"), (0, None, Some('x'), 0, "")], vec![]);
+        assert!(mixed.to_turing_machine().is_err());
+
+        // An epsilon loop that only pops runs out of stack, as with the A -> ε moves of a grammar
+        let popping = pda(3, "01", "x$", &[
+            (0, None, None, 1, "$"),
+            (1, Some('0'), None, 1, "x"),
+            (1, None, Some('x'), 1, ""),
+            (1, None, Some('$'), 2, ""),
+        ], vec![2]);
+        let machine = popping.to_turing_machine().unwrap();
+        for word in all_words(&['0', '1'], 3) {
+            let mut simulation = popping.compute(&word).unwrap();
+            simulation.run();
+            let mut computation = machine.bounded_compute(&word, (Some(20_000_000), None)).unwrap();
+            computation.run();
+            assert_eq!(computation.view_status(), simulation.view_status(), "on word {:?}", word);
+        }
+
+        // An epsilon loop that keeps pushing runs forever either way
+        let pushing = pda(1, "0", "x", &[(0, None, None, 0, "x")], vec![]);
+        let machine = pushing.to_turing_machine().unwrap();
+        let mut computation = machine.bounded_compute("", (Some(100_000), None)).unwrap();
+        computation.run();
+        assert_eq!(computation.view_status(), &ComputationStatus::Timeout);
+    }
+
+    #[test]
+    fn lba_confines_head() {
+        use crate::deterministic::builder::Builder;
+        use crate::deterministic::lba::RightEdge;
+
+        let machine = last_symbol_machine();
+        for word in all_words(&['0', '1'], 5) {
+            let mut computation = machine.lba_compute(&word, RightEdge::Error, None).unwrap();
+            computation.run().unwrap();
+            assert_eq!(computation.view_status(), run_word(&machine, &word).view_status(), "on word {:?}", word);
+            assert_eq!(computation.view_tape().len(), word.len() + 2);
+        }
+
+        let mut b = Builder::new(&['_', '>'], &['0']);
+        b.rule("start", ">0_", None, 1, "start");
+        let runner = b.build();
+
+        let mut computation = runner.lba_compute("00", RightEdge::Reject, None).unwrap();
+        computation.run().unwrap();
+        assert_eq!(computation.view_status(), &ComputationStatus::Reject);
+        assert_eq!((computation.view_clock().time, computation.view_head_position()), (4, 3));
+        assert!(!computation.view_looped());
+
+        let mut computation = runner.lba_compute("00", RightEdge::Reject, Some(4)).unwrap();
+        computation.run().unwrap();
+        assert_eq!(computation.view_status(), &ComputationStatus::Timeout);
+
+        let mut computation = runner.lba_compute("00", RightEdge::Error, None).unwrap();
+        assert!(computation.run().is_err());
+        assert_eq!(computation.view_status(), &ComputationStatus::Executing);
+    }
+
+    #[test]
+    fn lba_detects_loops() {
+        use crate::deterministic::builder::Builder;
+        use crate::deterministic::lba::RightEdge;
+
+        // Bounces between the start marker and the end of the input forever
+        let mut b = Builder::new(&['_', '>'], &['0']);
+        b.rule("start", ">", None, 1, "right");
+        b.rule("right", "0", None, 1, "right");
+        b.rule("right", "_", None, -1, "left");
+        b.rule("left", "0", None, -1, "left");
+        b.rule("left", ">", None, 1, "right");
+        let bouncer = b.build();
+
+        for word in ["", "0", "0000"] {
+            let mut computation = bouncer.lba_compute(word, RightEdge::Error, None).unwrap();
+            computation.run().unwrap();
+            assert_eq!(computation.view_status(), &ComputationStatus::Reject);
+            assert!(computation.view_looped());
+            assert!(computation.view_clock().time <= 4 * (2 * word.len() + 3));
+        }
+
+        let mut computation = bouncer.lba_compute("0000", RightEdge::Error, Some(5)).unwrap();
+        computation.run().unwrap();
+        assert_eq!(computation.view_status(), &ComputationStatus::Timeout);
+    }
+
+    #[test]
+    fn langtons_ant_builds_highway() {
+        let ant = crate::grid::langtons_ant();
+        let mut computation = ant.compute((None, None)).unwrap();
+        let mut positions = vec![computation.view_head_position()];
+        for _ in 0..12_000 {
+            computation.step();
+            positions.push(computation.view_head_position());
+        }
+
+        // Find where the ant settles into moving two cells diagonally every 104 steps
+        let drift = |t: usize| (positions[t + 104].0 - positions[t].0, positions[t + 104].1 - positions[t].1);
+        let highway = drift(positions.len() - 105);
+        assert_eq!((highway.0.abs(), highway.1.abs()), (2, 2));
+
+        let mut start = positions.len() - 105;
+        while start > 0 && drift(start - 1) == highway {
+            start -= 1;
+        }
+        assert!((9_900..10_100).contains(&start), "highway started at step {}", start);
+
+        let bounds = computation.view_grid().bounds();
+        assert_eq!(computation.view_clock().space, bounds.area());
+        assert_eq!(computation.render().lines().count(), bounds.height());
+    }
+
+    #[test]
+    fn grid_machine_moves_and_halts() {
+        use crate::grid::{ GridMachine, GridTransition, Heading, Movement, SparseGrid };
+
+        // Walk east over filled cells, then turn south and accept on the next blank
+        let machine = GridMachine::new(
+            vec![ State::new_state("east".to_string()), State::new_state("accept".to_string()), State::new_state("reject".to_string()), State::new_state("south".to_string()) ],
+            vec![ Symbol::new_symbol('.'), Symbol::new_symbol('#'), Symbol::new_symbol('o') ],
+            vec![
+                vec![
+                    GridTransition::new_transition(3, 2, Movement::Absolute(Heading::South)),
+                    GridTransition::new_transition(0, 2, Movement::Absolute(Heading::East)),
+                    GridTransition::new_transition(2, 2, Movement::Stay),
+                ],
+                vec![],
+                vec![],
+                vec![
+                    GridTransition::new_transition(1, 2, Movement::Stay),
+                    GridTransition::new_transition(3, 2, Movement::Relative(0)),
+                    GridTransition::new_transition(2, 2, Movement::Stay),
+                ],
+            ],
+            0, 1, 2
+        );
+
+        let grid = SparseGrid::load(&[vec![1, 1, 0], vec![0, 0, 1]]);
+        let mut computation = machine.compute_on_grid(grid, (None, None)).unwrap();
+        computation.run();
+        assert_eq!(computation.view_status(), &ComputationStatus::Accept);
+        assert_eq!(computation.view_head_position(), (2, 2));
+        assert_eq!(computation.view_clock().time, 5);
+        assert_eq!(computation.render(), "ooo\n..o\n..o");
+
+        let ant = crate::grid::langtons_ant();
+        let mut computation = ant.compute((None, Some(50))).unwrap();
+        computation.run();
+        assert_eq!(computation.view_status(), &ComputationStatus::Spaceout);
+
+        assert!(machine.compute_on_grid(SparseGrid::load(&[vec![3]]), (None, None)).is_err());
+    }
+
+    /// Two heads decide $\{ 0^n 1^n \}$: head 1 runs ahead to the first 1, then both heads step
+    /// together while head 0 reads 0s and head 1 reads 1s
+    fn two_head_zeros_then_ones() -> crate::deterministic::multihead::MultiHeadMachine {
+        use crate::deterministic::multihead::{ MultiHeadMachine, MultiTransition };
+
+        let names = ["start", "accept", "reject", "seek", "match"];
+        MultiHeadMachine::from_fn(
+            2,
+            names.iter().map(|name| State::new_state(name.to_string())).collect(),
+            vec![ Symbol::new_symbol('0'), Symbol::new_symbol('1') ],
+            vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ],
+            0, 1, 2,
+            |state, reads| {
+                let go = |next: usize, moves: Vec<i32>| MultiTransition::new_transition(next, reads.to_vec(), moves);
+                match (state, reads) {
+                    (0, _) => go(3, vec![1, 1]),
+                    (3, [_, 2]) => go(3, vec![0, 1]),
+                    (3, _) => go(4, vec![0, 0]),
+                    (4, [2, 3]) => go(4, vec![1, 1]),
+                    (4, [3, 0]) | (4, [0, 0]) => go(1, vec![0, 0]),
+                    _ => go(2, vec![0, 0])
+                }
+            }
+        ).unwrap()
+    }
+
+    #[test]
+    fn multi_head_computation() {
+        use crate::deterministic::multihead::{ MultiHeadMachine, MultiTransition };
+
+        let machine = two_head_zeros_then_ones();
+        for word in all_words(&['0', '1'], 8) {
+            let mut computation = machine.compute(&word, (None, None)).unwrap();
+            computation.run();
+            let expected = if is_zeros_then_ones(&word) { ComputationStatus::Accept } else { ComputationStatus::Reject };
+            assert_eq!(computation.view_status(), &expected, "on word {:?}", word);
+        }
+
+        let mut computation = machine.compute("0011", (None, None)).unwrap();
+        computation.run();
+        assert_eq!(computation.view_clock().time, 7);
+        assert_eq!(computation.view_head_positions(), &vec![3, 5]);
+
+        // Both heads write to the same cell and head 0 wins, then a head moving left stops at the start
+        let conflict = MultiHeadMachine::from_fn(
+            2,
+            ["start", "accept", "reject", "write", "check", "clamp"].iter().map(|name| State::new_state(name.to_string())).collect(),
+            vec![ Symbol::new_symbol('0'), Symbol::new_symbol('1') ],
+            vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ],
+            0, 1, 2,
+            |state, reads| match (state, reads) {
+                (0, _) => MultiTransition::new_transition(3, reads.to_vec(), vec![1, 1]),
+                (3, _) => MultiTransition::new_transition(5, vec![3, 2], vec![0, -1]),
+                (5, _) => MultiTransition::new_transition(4, reads.to_vec(), vec![0, -1]),
+                (4, [3, 1]) => MultiTransition::new_transition(1, reads.to_vec(), vec![0, 0]),
+                _ => MultiTransition::new_transition(2, reads.to_vec(), vec![0, 0])
+            }
+        ).unwrap();
+        let mut computation = conflict.compute("0", (None, None)).unwrap();
+        computation.run();
+        assert_eq!(computation.view_status(), &ComputationStatus::Accept);
+        assert_eq!(computation.view_tape(), &vec![1, 3]);
+        assert_eq!(computation.view_head_positions(), &vec![1, 0]);
+        let single = conflict.to_single_head().unwrap();
+        assert_eq!(run_word(&single, "0").view_status(), &ComputationStatus::Accept);
+        let mut computation = conflict.compute("", (None, Some(2))).unwrap();
+        computation.run();
+        assert_eq!(computation.view_status(), &ComputationStatus::Spaceout);
+
+        assert!(MultiHeadMachine::new(2, vec![State::new_state("q".to_string())], vec![], vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ], vec![vec![]], 0, 0, 0).is_ok());
+        assert!(MultiHeadMachine::new(0, vec![State::new_state("q".to_string())], vec![], vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ], vec![vec![]], 0, 0, 0).is_err());
+        assert!(MultiHeadMachine::new(2, vec![State::new_state("q".to_string())], vec![], vec![ Symbol::new_symbol('_') ], vec![vec![]], 0, 0, 0).is_err());
+
+        // Head counts are checked before anything is tabulated
+        let states = || ["start", "accept", "reject"].iter().map(|name| State::new_state(name.to_string())).collect();
+        let markers = || vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ];
+        let never = |_: usize, _: &[usize]| -> MultiTransition { panic!("Transition tabulated") };
+        assert!(MultiHeadMachine::from_fn(64, states(), vec![ Symbol::new_symbol('0') ], markers(), 0, 1, 2, never).is_err());
+        let digits = "0123456".chars().map(Symbol::new_symbol).collect();
+        assert!(MultiHeadMachine::from_fn(8, states(), digits, markers(), 0, 1, 2, never).err().unwrap().message.contains("too many"));
+    }
+
+    #[test]
+    fn multi_head_to_single_head() {
+        let machine = two_head_zeros_then_ones();
+        let single = machine.to_single_head().unwrap();
+        single.validate().unwrap();
+        check_decides(&single, &['0', '1'], 7, is_zeros_then_ones);
+
+        assert!(TuringMachine::default().validate().is_ok());
+        let incomplete = TuringMachine::new(
+            vec![ State::new_state("start".to_string()), State::new_state("accept".to_string()), State::new_state("reject".to_string()) ],
+            vec![ Symbol::new_symbol('0') ],
+            vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ],
+            vec![ vec![ Transition::new_transition(1, 0, 1) ] ],
+            0, 1, 2
+        );
+        assert!(incomplete.validate().is_err());
+    }
+
+    /// Copies the input to the query tape and asks the oracle, accepting on `yes_to` and rejecting on `no_to`
+    fn ask_oracle_machine(yes_to: &str, no_to: &str) -> crate::deterministic::oracle::OracleMachine {
+        use crate::deterministic::builder::Builder;
+        use crate::deterministic::oracle::{ OracleMachine, QueryAction };
+
+        let mut b = Builder::new(&['_', '>'], &['0', '1']);
+        b.rule("start", ">", None, 1, "copy");
+        b.rule("copy", "01", None, 1, "copy");
+        b.rule("copy", "_", None, 0, "ask");
+        b.rule("yes", "_", None, 0, yes_to);
+        b.rule("no", "_", None, 0, no_to);
+        let machine = b.build();
+
+        let id = |name: &str| machine.view_states().iter().position(|state| state.as_str() == name).unwrap();
+        let mut actions = vec![vec![]; machine.view_states().len()];
+        actions[id("copy")] = vec![ QueryAction::Nothing, QueryAction::Clear, QueryAction::Append(2), QueryAction::Append(3) ];
+        let (ask, yes, no) = (id("ask"), id("yes"), id("no"));
+
+        OracleMachine::new(machine, actions, ask, yes, no).unwrap()
+    }
+
+    #[test]
+    fn oracle_machines_relativize() {
+        let palindrome = |w: &str| w.chars().rev().collect::<String>() == w;
+        let decider = ask_oracle_machine("accept", "reject");
+        let complement = ask_oracle_machine("reject", "accept");
+
+        for word in all_words(&['0', '1'], 5) {
+            let mut computation = decider.compute(&palindrome, &word, (None, None)).unwrap();
+            computation.run();
+            let expected = if palindrome(&word) { ComputationStatus::Accept } else { ComputationStatus::Reject };
+            assert_eq!(computation.view_status(), &expected, "on word {:?}", word);
+            assert_eq!(computation.view_queries(), &vec![(word.clone(), palindrome(&word))]);
+            assert_eq!(computation.view_clock().time, word.len() + 3);
+
+            let mut computation = complement.compute(&is_zeros_then_ones, &word, (None, None)).unwrap();
+            computation.run();
+            let expected = if is_zeros_then_ones(&word) { ComputationStatus::Reject } else { ComputationStatus::Accept };
+            assert_eq!(computation.view_status(), &expected, "on word {:?}", word);
+            assert_eq!(computation.view_query(), "");
+        }
+
+        let mut computation = decider.compute(&palindrome, "0110", (Some(3), None)).unwrap();
+        computation.run();
+        assert_eq!(computation.view_status(), &ComputationStatus::Timeout);
+        assert_eq!(computation.view_query(), "0");
+        assert!(computation.view_queries().is_empty());
+    }
+
+    /// Steps onto the input, then on every input symbol continues with weight `keep` and rejects
+    /// with weight `drop`, and on the blank accepts, rejects or retries with equal weight
+    fn coin_machine(keep: u32, drop: u32) -> crate::probabilistic::ProbabilisticMachine {
+        let every = |f: &dyn Fn(usize) -> Vec<(u32, Transition)>| (0..4).map(f).collect::<Vec<_>>();
+        crate::probabilistic::ProbabilisticMachine::new(
+            ["start", "accept", "reject", "walk"].iter().map(|name| State::new_state(name.to_string())).collect(),
+            vec![ Symbol::new_symbol('0'), Symbol::new_symbol('1') ],
+            vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ],
+            vec![
+                every(&|symbol| vec![(1, Transition::new_transition(3, symbol, 1))]),
+                vec![],
+                vec![],
+                every(&|symbol| if symbol == 0 {
+                    vec![(1, Transition::new_transition(1, 0, 0)), (1, Transition::new_transition(2, 0, 0)), (1, Transition::new_transition(3, 0, 0))]
+                } else {
+                    vec![(keep, Transition::new_transition(3, symbol, 1)), (drop, Transition::new_transition(2, symbol, 1))]
+                }),
+            ],
+            0, 1, 2
+        ).unwrap()
+    }
+
+    #[test]
+    fn probabilistic_sampling_is_seeded() {
+        use crate::probabilistic::Random;
+        use crate::probabilistic::analysis::monte_carlo;
+
+        let mut a = Random::new(7);
+        let mut b = Random::new(7);
+        let first: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        assert_eq!(first, (0..5).map(|_| b.next_u64()).collect::<Vec<u64>>());
+        assert_ne!(first, (0..5).map(|_| Random::new(8).next_u64()).collect::<Vec<u64>>());
+        assert!((0..1000).all(|_| a.below(3) < 3 && (0.0..1.0).contains(&a.next_f64())));
+
+        // Accepting on the blank happens with probability 1/2, after halving once per input symbol
+        let machine = coin_machine(1, 1);
+        for (word, probability) in [("", 0.5), ("01", 0.125)] {
+            let estimate = monte_carlo(&machine, word, 4000, 42, (None, None)).unwrap();
+            assert_eq!(estimate, monte_carlo(&machine, word, 4000, 42, (None, None)).unwrap());
+            assert_eq!(estimate.accepted + estimate.rejected, 4000);
+
+            let (low, high) = estimate.interval(3.0);
+            assert!(low < probability && probability < high, "{:?} misses {}", (low, high), probability);
+            assert!(high - low < 0.1);
+        }
+
+        let estimate = monte_carlo(&machine, "0", 100, 1, (Some(2), None)).unwrap();
+        assert_eq!(estimate.unfinished, 100);
+        assert_eq!((estimate.probability(), estimate.interval(1.96).0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn probabilistic_exact_acceptance() {
+        use crate::probabilistic::analysis::exact_acceptance;
+
+        let machine = coin_machine(1, 1);
+        for (word, probability) in [("", 0.5), ("1", 0.25), ("0110", 0.5 / 16.0)] {
+            let exact = exact_acceptance(&machine, word, 10, 100).unwrap();
+            assert!((exact - probability).abs() < 1e-12, "{} on {:?}", exact, word);
+        }
+
+        let biased = coin_machine(3, 1);
+        let exact = exact_acceptance(&biased, "00", 10, 100).unwrap();
+        assert!((exact - 0.5 * 0.75 * 0.75).abs() < 1e-12);
+
+        // Running into the space limit never accepts
+        assert_eq!(exact_acceptance(&machine, "0", 3, 100).unwrap(), 0.0);
+        assert!(exact_acceptance(&machine, "0110", 3, 100).is_err());
+        assert!(exact_acceptance(&machine, "0110", 10, 2).is_err());
+    }
+
+    /// An alternating machine over `0` and `1` with the states `start`, `accept`, `reject`, `q3`, ...
+    /// where `rows[q][s]` lists `(next, write, direction)` choices
+    fn atm(kinds: Vec<crate::alternating::StateKind>, rows: Vec<Vec<Vec<(usize, usize, i32)>>>) -> crate::alternating::AlternatingMachine {
+        crate::alternating::AlternatingMachine::new(
+            (0..kinds.len()).map(|i| State::new_state(["start", "accept", "reject"].get(i).map_or(format!("q{i}"), |name| name.to_string()))).collect(),
+            kinds,
+            vec![ Symbol::new_symbol('0'), Symbol::new_symbol('1') ],
+            vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ],
+            rows.into_iter().map(|row| row.into_iter().map(|choices| {
+                choices.into_iter().map(|(next, write, direction)| Transition::new_transition(next, write, direction)).collect()
+            }).collect()).collect(),
+            0
+        ).unwrap()
+    }
+
+    #[test]
+    fn alternating_evaluation() {
+        use crate::alternating::StateKind::*;
+
+        // Universally over every 1, guess a later 0
+        let machine = atm(vec![Universal, Accept, Reject, Existential], vec![
+            vec![ vec![(1, 0, 0)], vec![(0, 1, 1)], vec![(0, 2, 1)], vec![(0, 3, 1), (3, 3, 1)] ],
+            vec![],
+            vec![],
+            vec![ vec![(2, 0, 0)], vec![], vec![(1, 2, 0), (3, 2, 1)], vec![(3, 3, 1)] ]
+        ]);
+
+        for length in 0..=6 {
+            for n in 0..(1 << length) {
+                let word: String = (0..length).map(|i| if n >> i & 1 == 1 { '1' } else { '0' }).collect();
+                let expected = word.rfind('1').is_none_or(|last| word[last..].contains('0'));
+                let evaluation = machine.evaluate(&word, (None, None)).unwrap();
+                assert_eq!(evaluation.status, if expected { ComputationStatus::Accept } else { ComputationStatus::Reject }, "{}", word);
+            }
+        }
+
+        assert_eq!(machine.evaluate("110", (Some(5), None)).unwrap().status, ComputationStatus::Timeout);
+        assert_eq!(machine.evaluate("110", (Some(6), None)).unwrap().status, ComputationStatus::Accept);
+        assert_eq!(machine.evaluate("11", (None, Some(4))).unwrap().status, ComputationStatus::Spaceout);
+        assert!(machine.evaluate("2", (None, None)).is_err());
+    }
+
+    #[test]
+    fn alternating_memoizes_and_detects_cycles() {
+        use crate::alternating::StateKind::*;
+
+        // Two identical branches per cell give a tree of 2^30 leaves over only 32 configurations
+        let doubling = atm(vec![Existential, Accept, Reject], vec![
+            vec![ vec![(2, 0, 0)], vec![(0, 1, 1), (0, 1, 1)], vec![(0, 2, 1), (0, 2, 1)], vec![(0, 3, 1), (0, 3, 1)] ]
+        ]);
+        let evaluation = doubling.evaluate(&"01".repeat(15), (None, None)).unwrap();
+        assert_eq!(evaluation, crate::alternating::Evaluation { status: ComputationStatus::Reject, configurations: 32 });
+
+        // Staying put forever is no proof of acceptance, but an existential state can avoid it
+        for (kind, status) in [(Existential, ComputationStatus::Accept), (Universal, ComputationStatus::Timeout)] {
+            let looping = atm(vec![kind, Accept, Reject], vec![
+                vec![ vec![(1, 0, 0)], vec![(0, 1, 0), (1, 1, 0)], vec![(1, 2, 0)], vec![(1, 3, 0)] ]
+            ]);
+            assert_eq!(looping.evaluate("", (None, None)).unwrap().status, status);
+        }
+
+        // The cut from X back to Y must not be remembered once Y has accepted through its other choice
+        let rejoining = atm(vec![Universal, Accept, Reject, Existential, Existential, Existential, Existential], vec![
+            vec![ vec![], vec![(3, 1, 0), (5, 1, 0)], vec![], vec![] ],
+            vec![],
+            vec![],
+            vec![ vec![], vec![(4, 1, 0), (1, 1, 0)], vec![], vec![] ],
+            vec![ vec![], vec![(3, 1, 0)], vec![], vec![] ],
+            vec![ vec![], vec![(6, 1, 0)], vec![], vec![] ],
+            vec![ vec![], vec![(4, 1, 0)], vec![], vec![] ]
+        ]);
+        assert_eq!(rejoining.evaluate("", (None, None)).unwrap().status, ComputationStatus::Accept);
+
+        // A branch that keeps growing the tape is refused instead of exhausting memory
+        let running = atm(vec![Existential, Accept, Reject], vec![
+            vec![ vec![(0, 0, 1)], vec![(0, 1, 1)], vec![(0, 2, 1)], vec![(0, 3, 1)] ]
+        ]);
+        assert!(running.evaluate("", (Some(1_000_000), None)).is_err());
+        assert_eq!(running.evaluate("", (Some(100_000), Some(1_000))).unwrap().status, ComputationStatus::Spaceout);
+
+        assert!(crate::alternating::AlternatingMachine::new(
+            vec![ State::new_state("start".to_string()) ], vec![],
+            vec![], vec![ Symbol::new_symbol('_'), Symbol::new_symbol('>') ], vec![], 0
+        ).is_err());
+    }
+
+    #[test]
+    fn enumerator_prints_lazily() {
+        use crate::deterministic::builder::Builder;
+        use crate::deterministic::enumerator::Enumerator;
+
+        // Prints 1, 11, 111, ... by appending a 1 and printing from the start marker
+        let mut b = Builder::new(&['_', '>'], &['0', '1']);
+        b.rule("start", ">1", None, 1, "start");
+        b.rule("start", "_", Some('1'), -1, "rewind");
+        b.rule("rewind", "1", None, -1, "rewind");
+        b.rule("rewind", ">", None, 0, "print");
+        b.rule("print", ">", None, 0, "start");
+        let print = b.state("print");
+        let enumerator = Enumerator::new(b.build(), print).unwrap();
+
+        let printed: Vec<String> = enumerator.enumerate(None).unwrap().take(4).collect();
+        assert_eq!(printed, ["1", "11", "111", "1111"]);
+
+        let mut enumeration = enumerator.enumerate(Some(20)).unwrap();
+        assert_eq!(enumeration.by_ref().count(), 3);
+        assert_eq!(enumeration.view_status(), &ComputationStatus::Timeout);
+
+        let recognizer = enumerator.to_recognizer().unwrap();
+        recognizer.validate().unwrap();
+        for (word, status) in [("1", ComputationStatus::Accept), ("111", ComputationStatus::Accept), ("", ComputationStatus::Timeout), ("101", ComputationStatus::Timeout)] {
+            let mut computation = recognizer.bounded_compute(word, (Some(5_000), None)).unwrap();
+            computation.run();
+            assert_eq!(computation.view_status(), &status, "on word {:?}", word);
+        }
+
+        assert!(Enumerator::new(TuringMachine::default(), 1).is_err());
+    }
+
+    #[test]
+    fn decider_enumerates_in_shortlex_order() {
+        use crate::deterministic::catalogue::palindromes;
+        use crate::deterministic::enumerator::Enumerator;
+
+        let decider = palindromes();
+        let enumerator = Enumerator::from_decider(&decider).unwrap();
+        let expected: Vec<String> = all_words(&['0', '1'], 5).into_iter().filter(|w| w.chars().eq(w.chars().rev())).collect();
+        let printed: Vec<String> = enumerator.enumerate(None).unwrap().take(expected.len()).collect();
+        assert_eq!(printed, expected);
+
+        // Converting back recognizes the language, halting exactly on its words
+        let recognizer = enumerator.to_recognizer().unwrap();
+        for word in all_words(&['0', '1'], 3) {
+            let mut computation = recognizer.bounded_compute(&word, (Some(200_000), None)).unwrap();
+            computation.run();
+            let expected = if word.chars().eq(word.chars().rev()) { ComputationStatus::Accept } else { ComputationStatus::Timeout };
+            assert_eq!(computation.view_status(), &expected, "on word {:?}", word);
+        }
+    }
+
+    #[test]
+    fn number_encodings() {
+        use crate::deterministic::encoding::{ Encoding, Unary, Positional, encode_tuple, decode_tuple };
+
+        let binary = Positional::binary();
+        let ternary = Positional::new(&['a', 'b', 'c']).unwrap();
+        for value in [0, 1, 2, 5, 64, 1000] {
+            assert_eq!(binary.encode(value), format!("{:b}", value));
+            assert_eq!(binary.decode(&binary.encode(value)).unwrap(), value);
+            assert_eq!(ternary.decode(&ternary.encode(value)).unwrap(), value);
+            assert_eq!(Unary('1').decode(&Unary('1').encode(value)).unwrap(), value);
+        }
+        assert_eq!(ternary.encode(5), "bc");
+        assert_eq!(binary.decode("0011").unwrap(), 3);
+        assert_eq!(binary.decode(&"1".repeat(64)).unwrap(), u64::MAX);
+
+        assert!(binary.decode("").is_err());
+        assert!(binary.decode("102").is_err());
+        assert!(binary.decode(&"1".repeat(65)).is_err());
+        assert!(Unary('1').decode("1101").is_err());
+        assert!(Positional::new(&['0']).is_err());
+        assert!(Positional::new(&['0', '1', '0']).is_err());
+
+        assert_eq!(encode_tuple(&[2, 0, 3], ',', &Unary('1')), "11,,111");
+        assert_eq!(decode_tuple("11,,111", &[','], &Unary('1')).unwrap(), [2, 0, 3]);
+        assert_eq!(decode_tuple("11*111=111111", &['*', '='], &Unary('1')).unwrap(), [2, 3, 6]);
+        assert!(decode_tuple("1,", &[','], &binary).is_err());
+    }
+
+    #[test]
+    fn transducers_compute_functions() {
+        use crate::deterministic::builder::Builder;
+        use crate::deterministic::catalogue::{ binary_addition, unary_multiplication };
+        use crate::deterministic::encoding::{ Encoding, Unary, Positional, encode_tuple, decode_tuple };
+        use crate::deterministic::transducer::Transducer;
+
+        let addition = Transducer::new(binary_addition(), 1).unwrap();
+        let binary = Positional::binary();
+        for (a, b) in [(0, 0), (3, 1), (13, 29), (255, 1)] {
+            let output = addition.apply(&encode_tuple(&[a, b], '+', &binary), (Some(100_000), None)).unwrap();
+            assert_eq!(binary.decode(&output).unwrap(), a + b);
+        }
+
+        let multiplication = Transducer::new(unary_multiplication(), 1).unwrap();
+        let output = multiplication.apply(&encode_tuple(&[3, 4], '*', &Unary('1')), (None, None)).unwrap();
+        assert_eq!(decode_tuple(&output, &['*', '='], &Unary('1')).unwrap(), [3, 4, 12]);
+
+        // The successor in unary, halting in its own state and rejecting anything but 1s
+        let mut b = Builder::new(&['_', '>'], &['0', '1']);
+        b.rule("start", ">1", None, 1, "start");
+        b.rule("start", "_", Some('1'), 0, "done");
+        let done = b.state("done");
+        let successor = Transducer::new(b.build(), done).unwrap();
+
+        let mut computation = successor.compute("11", (None, None)).unwrap();
+        assert_eq!(computation.view_output(), None);
+        computation.run();
+        assert_eq!(computation.view_status(), &ComputationStatus::Accept);
+        assert_eq!((computation.view_output().as_deref(), computation.view_clock().time), (Some("111"), 4));
+
+        let mut computation = successor.compute("101", (None, None)).unwrap();
+        computation.run();
+        assert_eq!((computation.view_status(), computation.view_output()), (&ComputationStatus::Reject, None));
+        assert!(successor.apply("101", (None, None)).is_err());
+        assert!(successor.apply("1111", (Some(3), None)).is_err());
+        assert!(Transducer::new(binary_addition(), 2).is_err());
+    }
+
+    #[test]
+    fn analysis_finds_dead_parts() {
+        use crate::deterministic::analysis::analyze;
+        use crate::deterministic::builder::Builder;
+
+        // Marks 0s with x, spins forever on a 1, and has an orphan writing the y nothing else writes
+        let mut b = Builder::new(&['_', '>', 'x', 'y'], &['0', '1']);
+        b.rule("start", ">", None, 1, "scan");
+        b.rule("scan", "0", Some('x'), 1, "scan");
+        b.rule("scan", "1", None, 1, "spin");
+        b.rule("scan", "_", None, 0, "accept");
+        let everything = b.symbols_except("");
+        b.rule("spin", &everything, None, 0, "spin");
+        b.rule("orphan", &everything, Some('y'), 1, "spin");
+        let machine = b.build();
+
+        let report = analyze(&machine);
+        assert_eq!(report.reachable_states, [0, 1, 2, 3, 4]);
+        assert_eq!(report.unreachable_states, [5]);
+        assert_eq!(report.unused_symbols, [3]);
+        assert_eq!(report.stuck_states, [4, 5]);
+        let mut dead = vec![(0, 3), (3, 3), (4, 3)];
+        dead.extend((0..6).map(|symbol| (5, symbol)));
+        assert_eq!(report.dead_transitions, dead);
+        assert!(!report.is_clean());
+
+        // Reading y is live once a reachable transition writes it
+        let mut b = Builder::new(&['_', '>', 'y'], &['0']);
+        b.rule("start", ">", None, 1, "write");
+        b.rule("write", "0_", Some('y'), -1, "start");
+        b.rule("start", "0y", None, 0, "accept");
+        let report = analyze(&b.build());
+        assert_eq!((report.unused_symbols.len(), report.dead_transitions.len(), report.stuck_states.len()), (0, 0, 0));
+        assert!(report.is_clean());
+
+        for machine in [crate::deterministic::catalogue::palindromes(), crate::deterministic::catalogue::binary_addition()] {
+            let report = analyze(&machine);
+            assert!(report.unreachable_states.is_empty() && report.stuck_states.is_empty() && report.unused_symbols.is_empty());
+        }
+    }
+
+    #[test]
+    fn minimization_preserves_behaviour() {
+        use crate::deterministic::builder::Builder;
+        use crate::deterministic::minimize::minimize;
+
+        // Two copies of the scanning state alternate, and an orphan can never be entered
+        let mut b = Builder::new(&['_', '>'], &['0', '1']);
+        b.rule("start", ">", None, 1, "even");
+        b.rule("even", "01", None, 1, "odd");
+        b.rule("odd", "01", None, 1, "even");
+        b.rule("even", "_", None, -1, "last");
+        b.rule("odd", "_", None, -1, "last");
+        b.rule("last", "0", None, 0, "accept");
+        b.rule("orphan", "01", None, 1, "last");
+        let doubled = b.build();
+
+        let minimal = minimize(&doubled).unwrap();
+        let names: Vec<&str> = minimal.view_states().iter().map(|state| state.as_str()).collect();
+        assert_eq!(names, ["start", "accept", "reject", "even", "last"]);
+        assert_eq!(minimize(&minimal).unwrap().view_states().len(), 5);
+
+        // A tape symbol that is never written leaves the only way into the orphan dead
+        let mut b = Builder::new(&['_', '>', 'y'], &['0']);
+        b.rule("start", ">", None, 1, "scan");
+        b.rule("scan", "0", None, 1, "scan");
+        b.rule("scan", "_", None, 0, "accept");
+        b.rule("scan", "y", None, 1, "orphan");
+        b.rule("orphan", "0_y", None, 0, "accept");
+        let stray = b.build();
+
+        let minimal = minimize(&stray).unwrap();
+        minimal.validate().unwrap();
+        let names: Vec<&str> = minimal.view_states().iter().map(|state| state.as_str()).collect();
+        assert_eq!(names, ["start", "accept", "reject", "scan"]);
+        for word in all_words(&['0'], 4) {
+            assert_eq!(run_word(&stray, &word).view_status(), run_word(&minimal, &word).view_status());
+        }
+
+        let program = crate::deterministic::program::compile(ENDS_IN_ZERO).unwrap();
+        let composed = crate::deterministic::compose::sequence(&doubled, &crate::deterministic::catalogue::palindromes()).unwrap();
+        let addition = crate::deterministic::catalogue::binary_addition();
+        for (machine, states) in [(&doubled, 5), (program.view_machine(), 7), (&composed, 12), (&addition, 14)] {
+            let minimal = minimize(machine).unwrap();
+            minimal.validate().unwrap();
+            assert_eq!(minimal.view_states().len(), states);
+
+            let alphabet: Vec<char> = machine.view_language_symbols().iter().map(|symbol| symbol.as_char()).collect();
+            for word in all_words(&alphabet, 5) {
+                let (original, reduced) = (run_word(machine, &word), run_word(&minimal, &word));
+                assert_eq!(original.view_status(), reduced.view_status(), "on word {:?}", word);
+                assert_eq!(original.view_clock().time, reduced.view_clock().time, "on word {:?}", word);
+                assert_eq!(tape_string(&original), tape_string(&reduced), "on word {:?}", word);
+            }
+        }
+    }
+
+    #[test]
+    fn dot_export() {
+        use crate::deterministic::builder::Builder;
+        use crate::deterministic::dot::{ DotOptions, trace };
+
+        let mut b = Builder::new(&['_', '>'], &['0', '1']);
+        b.rule("start", ">", None, 1, "scan \"right\"");
+        b.rule("scan \"right\"", "01", None, 1, "scan \"right\"");
+        b.rule("scan \"right\"", "_", None, -1, "last");
+        b.rule("last", "0", None, 0, "accept");
+        let machine = b.build();
+
+        let dot = machine.to_dot();
+        assert!(dot.starts_with("digraph {") && dot.ends_with("}\n"));
+        assert!(dot.contains("q1 [label=\"accept\", shape=doublecircle];"));
+        assert!(dot.contains("q2 [label=\"reject\", shape=octagon"));
+        assert!(dot.contains("q3 [label=\"scan \\\"right\\\"\"];"));
+        assert!(dot.contains("start -> q0;"));
+        assert!(dot.contains("q3 -> q3 [label=\"0→0,R1; 1→1,R1\"];"));
+        assert!(dot.contains("q4 -> q2 [label=\"_→_,S; >→>,S; 1→1,S\"];"));
+        assert_eq!(dot.matches(" -> ").count(), 8);
+
+        let states = trace(&machine, "10", (None, None)).unwrap();
+        assert_eq!(states, [0, 3, 3, 3, 4, 1]);
+        assert_eq!(trace(&machine, "10", (Some(3), None)).unwrap(), [0, 3, 3]);
+
+        let dot = machine.to_dot_with(&DotOptions { hide_reject: true, highlight: states });
+        assert!(!dot.contains("q2"));
+        assert_eq!(dot.matches(" -> ").count(), 5);
+        assert!(dot.contains("q4 -> q1 [label=\"0→0,S\", color=red, penwidth=2];"));
+        assert!(dot.contains("q0 [label=\"start\", color=red, penwidth=2];"));
+
+        let dot = machine.to_dot_with(&DotOptions { hide_reject: false, highlight: vec![0, 2] });
+        assert_eq!(dot.matches("color=red").count(), 3);
+    }
+
+    #[test]
+    fn space_time_diagrams() {
+        use crate::deterministic::diagram::{ DiagramOptions, SpaceTimeDiagram };
+
+        let machine = crate::deterministic::catalogue::binary_addition();
+        let mut computation = machine.bounded_compute("11+1", (None, None)).unwrap();
+        let diagram = SpaceTimeDiagram::record(&mut computation, &DiagramOptions::default());
+        assert_eq!(computation.view_status(), &ComputationStatus::Accept);
+        assert_eq!(diagram.view_rows().len(), 27);
+        assert_eq!(diagram.view_rows()[0], [1, 3, 3, 4, 3, 0, 0]);
+        assert_eq!(diagram.view_rows()[26][..computation.view_tape().len()], computation.view_tape().to_vec());
+        assert_eq!(diagram.view_heads()[..3], [0, 1, 2]);
+
+        // Sampling keeps the first and last configurations, and cropping keeps only the window
+        let mut computation = machine.bounded_compute("11+1", (None, None)).unwrap();
+        let options = DiagramOptions { every: 10, cells: Some((1, 4)), scale: 3 };
+        let diagram = SpaceTimeDiagram::record(&mut computation, &options);
+        assert_eq!(diagram.view_steps(), &vec![0, 10, 20, 26]);
+        assert_eq!(diagram.view_rows()[0], [3, 3, 4]);
+        assert_eq!(diagram.width(), 3);
+
+        let ppm = diagram.to_ppm();
+        let header = b"P6\n9 12\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(ppm.len(), header.len() + 9 * 12 * 3);
+        // The head starts on the start marker, outside the window
+        assert_ne!(&ppm[header.len()..header.len() + 3], &[230, 30, 30]);
+
+        let svg = diagram.to_svg();
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert!(svg.contains("width=\"9\" height=\"12\""));
+        assert_eq!(svg.matches("stroke=\"red\"").count(), diagram.view_heads().iter().filter(|head| (1..4).contains(*head)).count());
+    }
+
+    #[test]
+    fn computation_display() {
+        use crate::deterministic::display::DisplayOptions;
+
+        let machine = crate::deterministic::catalogue::binary_addition();
+        let mut computation = machine.bounded_compute("11+1", (None, None)).unwrap();
+        assert_eq!(computation.to_string(), "start: >11+1\n       ^");
+        assert_eq!(computation.display(DisplayOptions { window: Some(1), color: false }).to_string(), "start: >1…\n       ^");
+        computation.step();
+        computation.step();
+        assert_eq!(computation.to_string(), "to_end: >11+1\n          ^");
+
+        // The head on a blank keeps it shown, and windows cut off the far cells
+        for _ in 0..3 {
+            computation.step();
+        }
+        assert_eq!(computation.to_string(), "to_end: >11+1_\n             ^");
+        let windowed = computation.display(DisplayOptions { window: Some(1), color: false });
+        assert_eq!(windowed.to_string(), "to_end: …1_\n          ^");
+        let windowed = DisplayOptions { window: Some(2), color: false };
+        computation.step();
+        computation.step();
+        assert_eq!(computation.display(windowed).to_string(), "to_plus: …11+0\n            ^");
+
+        let colored = computation.display(DisplayOptions { window: None, color: true }).to_string();
+        assert_eq!(colored, "\x1b[1mto_plus\x1b[0m: >11\x1b[7m+\x1b[0m0\n            ^");
+
+        computation.run();
+        assert_eq!(computation.to_string(), "accept: >100\n        ^");
+    }
+
+    #[test]
+    fn latex_export() {
+        use crate::deterministic::builder::Builder;
+        use crate::deterministic::latex::{ escape, to_tikz, to_table, configuration, trace };
+
+        assert_eq!(escape("a_b>c#{}"), "a\\_b\\textgreater{}c\\#\\{\\}");
+        assert_eq!(escape("\\^~$%&"), "\\textbackslash{}\\textasciicircum{}\\textasciitilde{}\\$\\%\\&");
+
+        let mut b = Builder::new(&['_', '>'], &['0', '1']);
+        b.rule("start", ">", None, 1, "scan_0");
+        b.rule("scan_0", "01", None, 1, "scan_0");
+        b.rule("scan_0", "_", None, -1, "last");
+        b.rule("last", "0", None, 0, "accept");
+        let machine = b.build();
+
+        let tikz = to_tikz(&machine, false);
+        assert!(tikz.starts_with("\\begin{tikzpicture}") && tikz.ends_with("\\end{tikzpicture}\n"));
+        assert!(tikz.contains("\\node[state, initial] (q0) at (0, 0) {\\textit{start}};"));
+        assert!(tikz.contains("\\node[state, accepting] (q1) at (3, 0) {\\textit{accept}};"));
+        assert!(tikz.contains("(q3) at (0, -3) {\\textit{scan\\_0}};"));
+        assert!(tikz.contains("\\path (q3) edge[loop above] node[align=center] {\\texttt{0}$\\rightarrow$\\texttt{0},R1 \\\\ \\texttt{1}$\\rightarrow$\\texttt{1},R1} (q3);"));
+        assert!(tikz.contains("\\path (q0) edge node[align=center] {\\texttt{\\textgreater{}}$\\rightarrow$\\texttt{\\textgreater{}},R1} (q3);"));
+        assert_eq!(tikz.matches("\\path").count(), 7);
+        let hidden = to_tikz(&machine, true);
+        assert!(!hidden.contains("(q2)"));
+        assert_eq!(hidden.matches("\\path").count(), 4);
+
+        let table = to_table(&machine);
+        assert!(table.starts_with("\\begin{tabular}{l|llll}\n  & \\texttt{\\_} & \\texttt{\\textgreater{}} & \\texttt{0} & \\texttt{1} \\\\\n"));
+        assert!(table.contains("  \\textit{last} & \\textit{reject}, \\texttt{\\_}, S & \\textit{reject}, \\texttt{\\textgreater{}}, S & \\textit{accept}, \\texttt{0}, S & \\textit{reject}, \\texttt{1}, S \\\\\n"));
+        assert_eq!(table.matches("\\\\\n").count(), 4);
+
+        let mut computation = machine.compute("10").unwrap();
+        assert_eq!(configuration(&computation), "\\texttt{}\\,\\textit{start}\\,\\texttt{\\textgreater{}10}");
+        let lines = trace(&mut computation);
+        assert_eq!(lines.matches("\\textit{").count(), 6);
+        assert!(lines.contains("\\texttt{\\textgreater{}10}\\,\\textit{scan\\_0}\\,\\texttt{\\_} \\\\\n"));
+        assert!(lines.ends_with("\\texttt{\\textgreater{}1}\\,\\textit{accept}\\,\\texttt{0}\n\\end{tabular}\n"));
+    }
+
+    #[test]
+    fn machine_files() {
+        use crate::deterministic::catalogue::{ palindromes, binary_addition };
+        use crate::deterministic::format::{ from_text, to_text };
+
+        let text = "# Words ending in 0\ntape _ >\nlanguage 0 1\nstart q0\naccept yes\nreject no\n\nq0 > -> scan > R\nscan 0 -> scan 0 R\nscan 1 -> scan 1 R\nscan _ -> last _ L\nlast 0 -> yes 0 S\n";
+        let machine = from_text(text).unwrap();
+        assert_eq!(machine.view_states().len(), 5);
+        for (word, status) in [("10", ComputationStatus::Accept), ("01", ComputationStatus::Reject), ("", ComputationStatus::Reject)] {
+            let mut computation = machine.compute(word).unwrap();
+            computation.run();
+            assert_eq!(computation.view_status(), &status);
+        }
+
+        for machine in [palindromes(), binary_addition()] {
+            let copy = from_text(&to_text(&machine).unwrap()).unwrap();
+            assert_eq!(to_text(&copy).unwrap(), to_text(&machine).unwrap());
+            for word in ["", "0", "1", "01", "11", "010", "1+1", "10+11"] {
+                let (Ok(mut original), Ok(mut parsed)) = (machine.bounded_compute(word, (Some(10_000), None)), copy.bounded_compute(word, (Some(10_000), None))) else {
+                    continue;
+                };
+                original.run();
+                parsed.run();
+                assert_eq!(original.view_status(), parsed.view_status());
+                assert_eq!(original.view_output(), parsed.view_output());
+            }
+        }
+
+        assert!(from_text("tape _ >\nlanguage 0\nstart s\naccept a\nreject r\ns 0 -> a 0 S\ns 0 -> r 0 S\n").err().unwrap().message.contains("Line 7"));
+        assert!(from_text("tape _ >\nlanguage 0\nstart s\naccept a\nreject r\ns 2 -> a 0 S\n").is_err());
+        assert!(from_text("tape _ >\nlanguage 0\nstart s\naccept a\nreject r\na 0 -> s 0 S\n").is_err());
+        assert!(from_text("tape _ >\nlanguage 0\nstart s\naccept a\nreject r\ns 0 -> a 0 Up\n").is_err());
+        assert!(from_text("tape _ >\nlanguage 0\nstart s\naccept a\n").is_err());
+    }
+}
@@ -0,0 +1,194 @@
+//! Acceptance probabilities of probabilistic machines, estimated by sampling or computed exactly
+
+use std::collections::HashMap;
+
+use crate::errors::TmError;
+use crate::util::ComputationStatus;
+use super::machine::{ ProbabilisticMachine, ProbabilisticComputation };
+use super::random::Random;
+
+/// The outcomes of repeated runs of a machine on one word
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Estimate {
+    pub runs: usize,
+    pub accepted: usize,
+    pub rejected: usize,
+    /// Runs that timed out or ran out of space
+    pub unfinished: usize
+}
+
+impl Estimate {
+    /// The fraction of runs that accepted
+    pub fn probability(&self) -> f64 {
+        if self.runs == 0 { 0.0 } else { self.accepted as f64 / self.runs as f64 }
+    }
+
+    /// The Wilson score interval of the acceptance probability, where `z` is the standard normal
+    /// quantile of the confidence level (`1.96` for 95%)
+    pub fn interval(&self, z: f64) -> (f64, f64) {
+        if self.runs == 0 {
+            return (0.0, 1.0);
+        }
+
+        let n = self.runs as f64;
+        let p = self.probability();
+        let denominator = 1.0 + z * z / n;
+        let centre = (p + z * z / (2.0 * n)) / denominator;
+        let spread = z * (p * (1.0 - p) / n + z * z / (4.0 * n * n)).sqrt() / denominator;
+
+        ((centre - spread).max(0.0), (centre + spread).min(1.0))
+    }
+}
+
+/// Run the machine `runs` times on a word, drawing every run from one generator seeded with `seed`
+pub fn monte_carlo(machine: &ProbabilisticMachine, word: &str, runs: usize, seed: u64, limits: (Option<usize>, Option<usize>)) -> Result<Estimate, TmError> {
+    let mut estimate = Estimate { runs, accepted: 0, rejected: 0, unfinished: 0 };
+    let mut random = Random::new(seed);
+
+    for _ in 0..runs {
+        let (status, next) = ProbabilisticComputation::start(machine, word, random, limits)?.finish();
+        random = next;
+
+        match status {
+            ComputationStatus::Accept => estimate.accepted += 1,
+            ComputationStatus::Reject => estimate.rejected += 1,
+            _ => estimate.unfinished += 1
+        }
+    }
+
+    Ok(estimate)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Configuration {
+    state: usize,
+    head: usize,
+    cells: Vec<usize>
+}
+
+/// Where a transition from a configuration leads
+enum Outcome {
+    Continue(usize),
+    Accept,
+    Halt
+}
+
+/// The probability that the machine eventually accepts a word
+///
+/// Every configuration reachable while the tape stays below `max_space` cells is enumerated, with
+/// runs that reach the space limit counted as not accepting, and the absorption probabilities of the
+/// resulting Markov chain are solved exactly. Fails when there are more than `max_configurations`
+/// configurations.
+pub fn exact_acceptance(machine: &ProbabilisticMachine, word: &str, max_space: usize, max_configurations: usize) -> Result<f64, TmError> {
+    let initial = Configuration { state: machine.view_start_state(), head: 0, cells: machine.encode(word)? };
+    if initial.cells.len() >= max_space {
+        return Err(TmError::new(format!("The word needs {} cells, more than the space limit {}.", initial.cells.len(), max_space)));
+    }
+
+    let mut ids: HashMap<Configuration, usize> = HashMap::from([(initial.clone(), 0)]);
+    let mut configurations = vec![initial];
+    let mut edges: Vec<Vec<(f64, Outcome)>> = vec![];
+
+    while edges.len() < configurations.len() {
+        let configuration = configurations[edges.len()].clone();
+        let choices = &machine.view_transitions()[configuration.state][configuration.cells[configuration.head]];
+        let total: u64 = choices.iter().map(|(weight, _)| *weight as u64).sum();
+
+        let mut row = vec![];
+        for (weight, transition) in choices.iter().filter(|(weight, _)| *weight > 0) {
+            let probability = *weight as f64 / total as f64;
+            let mut cells = configuration.cells.clone();
+            cells[configuration.head] = transition.write_symbol_id();
+            let head = (configuration.head as i64 + transition.direction() as i64).max(0) as usize;
+            if head >= cells.len() {
+                cells.resize(head + 1, 0);
+                if cells.len() >= max_space {
+                    row.push((probability, Outcome::Halt));
+                    continue;
+                }
+            }
+
+            let next = transition.next_state_id();
+            if next == machine.view_accept_state() {
+                row.push((probability, Outcome::Accept));
+            } else if next == machine.view_reject_state() {
+                row.push((probability, Outcome::Halt));
+            } else {
+                let next = Configuration { state: next, head, cells };
+                let id = match ids.get(&next) {
+                    Some(val) => *val,
+                    None => {
+                        if configurations.len() >= max_configurations {
+                            return Err(TmError::new(format!("More than {} configurations are reachable.", max_configurations)));
+                        }
+                        ids.insert(next.clone(), configurations.len());
+                        configurations.push(next);
+                        configurations.len() - 1
+                    }
+                };
+                row.push((probability, Outcome::Continue(id)));
+            }
+        }
+        edges.push(row);
+    }
+
+    // Only configurations that can still accept need solving, the rest accept with probability zero
+    let mut can_accept: Vec<bool> = edges.iter().map(|row| row.iter().any(|(_, outcome)| matches!(outcome, Outcome::Accept))).collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (i, row) in edges.iter().enumerate() {
+            if !can_accept[i] && row.iter().any(|(_, outcome)| matches!(outcome, Outcome::Continue(j) if can_accept[*j])) {
+                can_accept[i] = true;
+                changed = true;
+            }
+        }
+    }
+
+    if !can_accept[0] {
+        return Ok(0.0);
+    }
+
+    // Solve p_i - sum_j P_ij p_j = P_i,accept over the configurations that can accept
+    let unknowns: Vec<usize> = (0..edges.len()).filter(|i| can_accept[*i]).collect();
+    let index: HashMap<usize, usize> = unknowns.iter().enumerate().map(|(k, i)| (*i, k)).collect();
+    let n = unknowns.len();
+    let mut matrix = vec![vec![0.0; n + 1]; n];
+
+    for (k, i) in unknowns.iter().enumerate() {
+        matrix[k][k] += 1.0;
+        for (probability, outcome) in &edges[*i] {
+            match outcome {
+                Outcome::Accept => matrix[k][n] += probability,
+                Outcome::Continue(j) => if let Some(column) = index.get(j) {
+                    matrix[k][*column] -= probability;
+                },
+                Outcome::Halt => {}
+            }
+        }
+    }
+
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|a, b| matrix[*a][column].abs().total_cmp(&matrix[*b][column].abs()))
+            .unwrap();
+        matrix.swap(column, pivot);
+
+        let divisor = matrix[column][column];
+        for value in matrix[column].iter_mut() {
+            *value /= divisor;
+        }
+
+        let pivot_row = matrix[column].clone();
+        for (row, values) in matrix.iter_mut().enumerate() {
+            let factor = values[column];
+            if row != column && factor != 0.0 {
+                for (value, pivot) in values.iter_mut().zip(pivot_row.iter()).skip(column) {
+                    *value -= factor * pivot;
+                }
+            }
+        }
+    }
+
+    Ok(matrix[index[&0]][n].clamp(0.0, 1.0))
+}
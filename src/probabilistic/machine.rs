@@ -0,0 +1,160 @@
+use crate::deterministic::computation::encode_symbols;
+use crate::deterministic::machine::check_definition;
+use crate::errors::TmError;
+use crate::tape::{ Tape, DenseTape };
+use crate::util::{ ComputationStatus, ComputationClock, State, Symbol, Transition };
+use super::random::Random;
+
+/// A transition chosen with probability proportional to its weight among those for the same
+/// `(state, symbol)` pair
+pub type WeightedTransition = (u32, Transition);
+
+pub struct ProbabilisticMachine {
+    states: Vec<State>,
+    tape_symbols: Vec<Symbol>,
+    language_symbols: Vec<Symbol>,
+    transitions: Vec<Vec<Vec<WeightedTransition>>>,
+    start_state: usize,
+    accept_state: usize,
+    reject_state: usize
+}
+
+impl ProbabilisticMachine {
+    /// Create a machine where `transitions[q][s]` holds the weighted choices from state `q` on symbol `s`
+    pub fn new(states: Vec<State>, language_symbols: Vec<Symbol>, tape_symbols: Vec<Symbol>, transitions: Vec<Vec<Vec<WeightedTransition>>>, start_state: usize, accept_state: usize, reject_state: usize) -> Result<Self, TmError> {
+        check_definition(states.len(), &tape_symbols, &language_symbols, [start_state, accept_state, reject_state])?;
+
+        let symbols = tape_symbols.len() + language_symbols.len();
+        for (state, name) in states.iter().enumerate() {
+            if state == accept_state || state == reject_state {
+                continue;
+            }
+
+            let row = transitions.get(state).map_or(&[][..], |row| &row[..]);
+            if row.len() != symbols {
+                return Err(TmError::new(format!("State {} has {} sets of transitions for {} symbols.", name, row.len(), symbols)));
+            }
+
+            for (symbol, choices) in row.iter().enumerate() {
+                if choices.iter().map(|(weight, _)| *weight as u64).sum::<u64>() == 0 {
+                    return Err(TmError::new(format!("State {} has no weighted transition on symbol {}.", name, symbol)));
+                }
+
+                if choices.iter().any(|(_, t)| t.next_state_id() >= states.len() || t.write_symbol_id() >= symbols) {
+                    return Err(TmError::new(format!("State {} has a transition to a missing state or symbol.", name)));
+                }
+            }
+        }
+
+        Ok(Self { states, tape_symbols, language_symbols, transitions, start_state, accept_state, reject_state })
+    }
+
+    /// Construct a bounded computation sampling with a generator seeded by `seed`
+    pub fn compute(&self, word: &str, seed: u64, limits: (Option<usize>, Option<usize>)) -> Result<ProbabilisticComputation<'_>, TmError> {
+        ProbabilisticComputation::start(self, word, Random::new(seed), limits)
+    }
+
+    /// The initial tape contents for a word
+    pub(crate) fn encode(&self, word: &str) -> Result<Vec<usize>, TmError> {
+        encode_symbols(&self.tape_symbols, &self.language_symbols, word)
+    }
+
+    pub fn view_states(&self) -> &Vec<State> { &self.states }
+    pub fn view_tape_symbols(&self) -> &Vec<Symbol> { &self.tape_symbols }
+    pub fn view_language_symbols(&self) -> &Vec<Symbol> { &self.language_symbols }
+    pub fn view_transitions(&self) -> &Vec<Vec<Vec<WeightedTransition>>> { &self.transitions }
+    pub fn view_start_state(&self) -> usize { self.start_state }
+    pub fn view_accept_state(&self) -> usize { self.accept_state }
+    pub fn view_reject_state(&self) -> usize { self.reject_state }
+}
+
+pub struct ProbabilisticComputation<'a> {
+    machine: &'a ProbabilisticMachine,
+    current_state: usize,
+    tape: DenseTape,
+    random: Random,
+    status: ComputationStatus,
+    clock: ComputationClock
+}
+
+impl ProbabilisticComputation<'_> {
+    pub fn view_machine(&self) -> &ProbabilisticMachine { self.machine }
+    pub fn view_current_state(&self) -> usize { self.current_state }
+    pub fn view_head_position(&self) -> usize { self.tape.head() }
+    pub fn view_tape(&self) -> &DenseTape { &self.tape }
+    pub fn view_status(&self) -> &ComputationStatus { &self.status }
+    pub fn view_clock(&self) -> &ComputationClock { &self.clock }
+
+    /// The generator, which continues from where this computation left it
+    pub fn view_random(&self) -> &Random { &self.random }
+}
+
+impl<'a> ProbabilisticComputation<'a> {
+    /// Create a bounded computation from a machine, word and generator
+    pub fn start(machine: &'a ProbabilisticMachine, word: &str, random: Random, limits: (Option<usize>, Option<usize>)) -> Result<Self, TmError> {
+        let symbols = machine.encode(word)?;
+        let tape = DenseTape::load(&symbols, machine.tape_symbols.len() + machine.language_symbols.len())?;
+
+        Ok(ProbabilisticComputation {
+            machine,
+            current_state: machine.start_state,
+            clock: ComputationClock::clock(limits.0, limits.1, tape.len()),
+            tape,
+            random,
+            status: ComputationStatus::Executing
+        })
+    }
+
+    /// Sample and execute the next transition from the current configuration
+    pub fn step(&mut self) {
+        if self.status != ComputationStatus::Executing {
+            return;
+        }
+
+        if let Some(status) = self.clock.tick() {
+            self.status = status;
+            return;
+        }
+
+        let choices = &self.machine.transitions[self.current_state][self.tape.read()];
+        let total: u64 = choices.iter().map(|(weight, _)| *weight as u64).sum();
+        let mut sample = self.random.below(total);
+        let transition = choices.iter()
+            .find(|(weight, _)| {
+                let found = sample < *weight as u64;
+                sample = sample.saturating_sub(*weight as u64);
+                found
+            })
+            .map(|(_, transition)| transition)
+            .unwrap();
+
+        self.current_state = transition.next_state_id();
+        self.tape.write(transition.write_symbol_id());
+
+        if self.tape.move_head(transition.direction()) > 0 {
+            if let Some(status) = self.clock.occupy(self.tape.len()) {
+                self.status = status;
+                return;
+            }
+        }
+
+        if self.current_state == self.machine.accept_state {
+            self.status = ComputationStatus::Accept;
+        } else if self.current_state == self.machine.reject_state {
+            self.status = ComputationStatus::Reject;
+        }
+    }
+
+    /// Run the Computation until it halts
+    pub fn run(&mut self) {
+        while self.status == ComputationStatus::Executing {
+            self.step();
+        }
+    }
+
+    /// Run the Computation until it halts and hand back the generator for the next run
+    pub(crate) fn finish(mut self) -> (ComputationStatus, Random) {
+        self.run();
+        (self.status, self.random)
+    }
+}
@@ -0,0 +1,15 @@
+//! # Probabilistic Turing Machines
+//!
+//! Machines where every `(state, symbol)` pair maps to a weighted set of transitions, one of which
+//! is sampled at each step. Sampling uses a small seeded generator so that runs are reproducible.
+//!
+//! * [`machine`] - The machine definition and a sampled computation
+//! * [`random`] - The seeded pseudo random number generator
+//! * [`analysis`] - Monte Carlo estimates and exact acceptance probabilities
+
+pub mod random;
+pub mod machine;
+pub mod analysis;
+
+pub use crate::probabilistic::machine::{ ProbabilisticMachine, ProbabilisticComputation };
+pub use crate::probabilistic::random::Random;
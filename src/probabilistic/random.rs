@@ -0,0 +1,52 @@
+//! A xoshiro256** generator seeded through SplitMix64
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Random {
+    state: [u64; 4]
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        let mut mix = seed;
+        let mut next = || {
+            mix = mix.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = mix;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+
+        Random { state: [next(), next(), next(), next()] }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let shifted = self.state[1] << 17;
+
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= shifted;
+        self.state[3] = self.state[3].rotate_left(45);
+
+        result
+    }
+
+    /// A uniformly distributed number in `0..bound`, without modulo bias
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "Cannot sample below zero");
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+
+    /// A uniformly distributed number in `[0, 1)`
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,116 @@
+//! # Pushdown Automata
+//!
+//! $$
+//! PDA = \left< Q, \Sigma, \Gamma, \delta, q_0, F \right>
+//! $$
+//!
+//! * $Q$ - The set of all states in the automaton
+//! * $\Sigma$ - The input symbols
+//! * $\Gamma$ - The stack symbols
+//! * $\delta$ - The transitions $Q \times \Sigma_\epsilon \times \Gamma_\epsilon \mapsto \mathcal{P}(Q \times \Gamma^*)$
+//! * $q_0$ - The start state
+//! * $F$ - The accepting states
+//!
+//! The stack starts empty and a word is accepted when some branch has read all of it and is in an
+//! accepting state.
+
+use crate::errors::TmError;
+use crate::util::{ State, Symbol };
+use crate::deterministic::DTM;
+use super::computation::Computation;
+
+/// A transition that reads an input symbol (or nothing), pops a stack symbol (or nothing), pushes
+/// symbols and enters the next state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transition {
+    from: usize,
+    read: Option<usize>,
+    pop: Option<usize>,
+    next: usize,
+    push: Vec<usize>
+}
+
+impl Transition {
+    /// The first symbol of `push` ends up on top of the stack
+    pub fn new_transition(from: usize, read: Option<usize>, pop: Option<usize>, next: usize, push: Vec<usize>) -> Self {
+        Transition { from, read, pop, next, push }
+    }
+
+    pub fn from_state_id(&self) -> usize { self.from }
+    pub fn read_symbol_id(&self) -> Option<usize> { self.read }
+    pub fn pop_symbol_id(&self) -> Option<usize> { self.pop }
+    pub fn next_state_id(&self) -> usize { self.next }
+    pub fn push_symbol_ids(&self) -> &Vec<usize> { &self.push }
+}
+
+pub struct PushdownAutomaton {
+    states: Vec<State>,
+    input_symbols: Vec<Symbol>,
+    stack_symbols: Vec<Symbol>,
+    transitions: Vec<Transition>,
+    start_state: usize,
+    accept_states: Vec<usize>
+}
+
+impl PushdownAutomaton {
+    pub fn new(states: Vec<State>, input_symbols: Vec<Symbol>, stack_symbols: Vec<Symbol>, transitions: Vec<Transition>, start_state: usize, accept_states: Vec<usize>) -> Result<Self, TmError> {
+        for (i, symbol) in input_symbols.iter().enumerate() {
+            if symbol.as_char() == '_' || symbol.as_char() == '>' {
+                return Err(TmError::new(format!("Symbol {} is reserved and cannot be an input symbol.", symbol)));
+            }
+
+            if input_symbols[..i].contains(symbol) {
+                return Err(TmError::new(format!("Input symbol {} appears more than once.", symbol)));
+            }
+        }
+
+        for (i, symbol) in stack_symbols.iter().enumerate() {
+            if stack_symbols[..i].contains(symbol) {
+                return Err(TmError::new(format!("Stack symbol {} appears more than once.", symbol)));
+            }
+        }
+
+        if let Some(state) = accept_states.iter().chain(std::iter::once(&start_state)).find(|state| **state >= states.len()) {
+            return Err(TmError::new(format!("State {} does not exist.", state)));
+        }
+
+        for (i, transition) in transitions.iter().enumerate() {
+            if transition.from >= states.len() || transition.next >= states.len() {
+                return Err(TmError::new(format!("Transition {} refers to a missing state.", i)));
+            }
+
+            if transition.read.is_some_and(|read| read >= input_symbols.len()) {
+                return Err(TmError::new(format!("Transition {} reads a missing input symbol.", i)));
+            }
+
+            if transition.pop.iter().chain(transition.push.iter()).any(|symbol| *symbol >= stack_symbols.len()) {
+                return Err(TmError::new(format!("Transition {} uses a missing stack symbol.", i)));
+            }
+        }
+
+        Ok(Self { states, input_symbols, stack_symbols, transitions, start_state, accept_states })
+    }
+
+    /// Construct an unbounded computation using the current automaton and a specified word
+    pub fn compute(&self, word: &str) -> Result<Computation<'_>, TmError> {
+        Computation::start(self, word, (None, None))
+    }
+
+    /// Construct a computation bounded in steps and stack height using the current automaton and a specified word
+    pub fn bounded_compute(&self, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<Computation<'_>, TmError> {
+        Computation::start(self, word, limits)
+    }
+
+    /// Convert the automaton into an equivalent single tape machine, failing on epsilon cycles that
+    /// can return to a state with the stack as high as before
+    pub fn to_turing_machine(&self) -> Result<DTM, TmError> {
+        super::convert::to_turing_machine(self)
+    }
+
+    pub fn view_states(&self) -> &Vec<State> { &self.states }
+    pub fn view_input_symbols(&self) -> &Vec<Symbol> { &self.input_symbols }
+    pub fn view_stack_symbols(&self) -> &Vec<Symbol> { &self.stack_symbols }
+    pub fn view_transitions(&self) -> &Vec<Transition> { &self.transitions }
+    pub fn view_start_state(&self) -> usize { self.start_state }
+    pub fn view_accept_states(&self) -> &Vec<usize> { &self.accept_states }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashSet;
+
+use crate::errors::TmError;
+use crate::util::{ ComputationStatus, ComputationClock, Symbol };
+use super::automaton::PushdownAutomaton;
+
+/// One branch of a computation: the state, how much of the input has been read and the stack,
+/// with the top of the stack last
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Configuration {
+    pub state: usize,
+    pub position: usize,
+    pub stack: Vec<usize>
+}
+
+/// A breadth first simulation of every branch of a pushdown automaton
+///
+/// Each step applies one transition to every live branch. A configuration that has already been
+/// seen is not explored again, so epsilon loops that do not grow the stack die out. The clock
+/// counts steps in `time` and the tallest stack reached in `space`.
+///
+/// The computation accepts as soon as a branch has read the whole input in an accepting state, and
+/// rejects once every branch has died. Branches whose stack reaches the space limit are dropped, and
+/// the computation ends in [`ComputationStatus::Spaceout`] instead of rejecting if any were.
+pub struct Computation<'a> {
+    automaton: &'a PushdownAutomaton,
+    word: Vec<usize>,
+    branches: Vec<Configuration>,
+    seen: HashSet<Configuration>,
+    dropped: bool,
+    status: ComputationStatus,
+    clock: ComputationClock
+}
+
+impl Computation<'_> {
+    pub fn view_automaton(&self) -> &PushdownAutomaton { self.automaton }
+    pub fn view_branches(&self) -> &Vec<Configuration> { &self.branches }
+    pub fn view_status(&self) -> &ComputationStatus { &self.status }
+    pub fn view_clock(&self) -> &ComputationClock { &self.clock }
+}
+
+impl<'a> Computation<'a> {
+    /// Create a bounded computation from an automaton and word
+    pub fn start(automaton: &'a PushdownAutomaton, word: &str, limits: (Option<usize>, Option<usize>)) -> Result<Self, TmError> {
+        let mut symbols = vec![];
+        for (i, w) in word.chars().enumerate() {
+            let symbol = Symbol::new_symbol(w);
+            match automaton.view_input_symbols().iter().position(|x| x == &symbol) {
+                Some(val) => symbols.push(val),
+                None => return Err(TmError::new(format!("Invalid symbol {} found at position {}.", w, i)))
+            }
+        }
+
+        let initial = Configuration { state: automaton.view_start_state(), position: 0, stack: vec![] };
+        let mut computation = Computation {
+            automaton,
+            word: symbols,
+            branches: vec![initial.clone()],
+            seen: HashSet::from([initial]),
+            dropped: false,
+            status: ComputationStatus::Executing,
+            clock: ComputationClock::clock(limits.0, limits.1, 0)
+        };
+
+        if computation.accepts(&computation.branches[0]) {
+            computation.status = ComputationStatus::Accept;
+        }
+
+        Ok(computation)
+    }
+
+    fn accepts(&self, configuration: &Configuration) -> bool {
+        configuration.position == self.word.len() && self.automaton.view_accept_states().contains(&configuration.state)
+    }
+
+    /// Apply every possible transition to every live branch
+    pub fn step(&mut self) {
+        if self.status != ComputationStatus::Executing {
+            return;
+        }
+
+        if let Some(status) = self.clock.tick() {
+            self.status = status;
+            return;
+        }
+
+        let mut next_branches = vec![];
+        for branch in &self.branches {
+            for transition in self.automaton.view_transitions() {
+                if transition.from_state_id() != branch.state {
+                    continue;
+                }
+
+                let position = match transition.read_symbol_id() {
+                    Some(read) if self.word.get(branch.position) == Some(&read) => branch.position + 1,
+                    Some(_) => continue,
+                    None => branch.position
+                };
+
+                let mut stack = branch.stack.clone();
+                if let Some(pop) = transition.pop_symbol_id() {
+                    if stack.pop() != Some(pop) {
+                        continue;
+                    }
+                }
+                stack.extend(transition.push_symbol_ids().iter().rev());
+
+                if !self.clock.fits(stack.len()) {
+                    self.dropped = true;
+                    continue;
+                }
+
+                let configuration = Configuration { state: transition.next_state_id(), position, stack };
+                if self.seen.insert(configuration.clone()) {
+                    self.clock.space = self.clock.space.max(configuration.stack.len());
+                    next_branches.push(configuration);
+                }
+            }
+        }
+
+        self.branches = next_branches;
+        if self.branches.iter().any(|branch| self.accepts(branch)) {
+            self.status = ComputationStatus::Accept;
+        } else if self.branches.is_empty() {
+            self.status = if self.dropped { ComputationStatus::Spaceout } else { ComputationStatus::Reject };
+        }
+    }
+
+    /// Run the Computation until it halts
+    pub fn run(&mut self) {
+        while self.status == ComputationStatus::Executing {
+            self.step();
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Conversion of pushdown automata into single tape machines
+//!
+//! The machine tries every sequence of nondeterministic choices in shortlex order. With the tape
+//! laid out as
+//!
+//! ```text
+//! > input | flag choices | stack
+//! ```
+//!
+//! it replays the automaton from the start for each sequence, consuming one choice per transition:
+//! the `i`th choice picks the `i`th transition out of the current state. Read input symbols and
+//! spent choices are marked, and the stack grows to the right with its top last.
+//!
+//! The machine accepts when a sequence runs out in an accepting state with the input read. A
+//! sequence that runs out otherwise sets the flag, and when no sequence of one length set it, every
+//! branch of the automaton has died and the machine rejects.
+//!
+//! The machine cannot tell when a branch returns to a configuration it has been in, which the
+//! [simulation](super::computation::Computation) prunes. A branch can only do that by following
+//! epsilon transitions around a strongly connected part of the automaton while leaving the stack as
+//! high as it was, so automata where such a part has a cycle that keeps the stack height, or has
+//! both a cycle that shrinks the stack and one that grows it, are refused. Cycles that only shrink
+//! the stack run out of symbols to pop, and for every automaton that is not refused the machine runs
+//! forever exactly when the simulation does.
+
+use std::collections::HashSet;
+
+use crate::deterministic::builder::Builder;
+use crate::deterministic::DTM;
+use crate::errors::TmError;
+use super::automaton::PushdownAutomaton;
+
+pub fn to_turing_machine(pda: &PushdownAutomaton) -> Result<DTM, TmError> {
+    if let Some(state) = neutral_epsilon_cycle(pda) {
+        return Err(TmError::new(format!(
+            "State {} is on cycles of epsilon transitions that can return to it with the stack as high as before.", pda.view_states()[state]
+        )));
+    }
+
+    let input: Vec<char> = pda.view_input_symbols().iter().map(|symbol| symbol.as_char()).collect();
+    let choices = (0..pda.view_states().len())
+        .map(|state| pda.view_transitions().iter().filter(|t| t.from_state_id() == state).count())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let mut fresh = ('\u{E000}'..='\u{F8FF}').filter(|c| !input.contains(c));
+    let mut take = |count: usize| -> Vec<char> { fresh.by_ref().take(count).collect() };
+    let (input_end, stack_start) = (take(1)[0], take(1)[0]);
+    let (alive, dead) = (take(1)[0], take(1)[0]);
+    let digits = take(choices);
+    let spent = take(choices);
+    let marked = take(input.len());
+    let stack = take(pda.view_stack_symbols().len());
+
+    let mut tape_symbols = vec!['_', '>', input_end, stack_start, alive, dead];
+    for symbols in [&digits, &spent, &marked, &stack] {
+        tape_symbols.extend_from_slice(symbols);
+    }
+    let mut b = Builder::new(&tape_symbols, &input);
+
+    let all = b.symbols_except("");
+    let string = |symbols: &[char]| -> String { symbols.iter().collect() };
+    let mut homes = HashSet::new();
+    let mut home = |b: &mut Builder, to: &str| -> String {
+        let name = format!("home_{to}");
+        if homes.insert(name.clone()) {
+            let everything = b.symbols_except(">");
+            b.rule(&name, &everything, None, -1, &name);
+            b.rule(&name, ">", None, 1, to);
+        }
+        name
+    };
+
+    // Lay out the tape with an empty choice sequence and stack
+    let start_run = home(&mut b, &format!("fetch_{}", pda.view_start_state()));
+    b.rule("start", ">", None, 1, "setup");
+    b.rule("setup", &string(&input), None, 1, "setup");
+    b.rule("setup", "_", Some(input_end), 1, "setup_flag");
+    b.rule("setup_flag", "_", Some(dead), 1, "setup_stack");
+    b.rule("setup_stack", "_", Some(stack_start), 0, &start_run);
+
+    for state in 0..pda.view_states().len() {
+        let fetch = format!("fetch_{state}");
+        let end = format!("end_{state}");
+        let outgoing: Vec<usize> = (0..pda.view_transitions().len())
+            .filter(|t| pda.view_transitions()[*t].from_state_id() == state)
+            .collect();
+
+        // Find the first unspent choice and pick that transition
+        let skipped = b.symbols_except(&string(&digits)).replace(stack_start, "");
+        b.rule(&fetch, &skipped, None, 1, &fetch);
+        b.rule(&fetch, &stack_start.to_string(), None, 0, &end);
+        for (choice, digit) in digits.iter().enumerate() {
+            match outgoing.get(choice) {
+                Some(t) => {
+                    let read = home(&mut b, &format!("read_{t}"));
+                    b.rule(&fetch, &digit.to_string(), Some(spent[choice]), 0, &read);
+                },
+                None => b.rule(&fetch, &digit.to_string(), None, 0, "dead")
+            }
+        }
+
+        // The choices ran out, so accept if the whole input was read in an accepting state
+        if pda.view_accept_states().contains(&state) {
+            let check = home(&mut b, "check_input");
+            b.rule(&end, &all, None, 0, &check);
+        } else {
+            b.rule(&end, &all, None, 0, "alive");
+        }
+    }
+
+    b.rule("check_input", &string(&marked), None, 1, "check_input");
+    b.rule("check_input", &input_end.to_string(), None, 0, "accept");
+    b.rule("check_input", &string(&input), None, 0, "alive");
+
+    for (t, transition) in pda.view_transitions().iter().enumerate() {
+        let read = format!("read_{t}");
+        let pop = format!("pop_{t}");
+        let top = format!("top_{t}");
+
+        match transition.read_symbol_id() {
+            Some(symbol) => {
+                let others: String = input.iter().filter(|c| **c != input[symbol]).chain([input_end].iter()).collect();
+                b.rule(&read, &string(&marked), None, 1, &read);
+                b.rule(&read, &input[symbol].to_string(), Some(marked[symbol]), 1, &pop);
+                b.rule(&read, &others, None, 0, "dead");
+            },
+            None => b.rule(&read, &all, None, 0, &pop)
+        }
+
+        let pushes = transition.push_symbol_ids();
+        let fetch_next = home(&mut b, &format!("fetch_{}", transition.next_state_id()));
+        let push_state = |i: usize| if i == pushes.len() { fetch_next.clone() } else { format!("push_{t}_{i}") };
+
+        let stacked = b.symbols_except("_");
+        b.rule(&pop, &stacked, None, 1, &pop);
+        b.rule(&pop, "_", None, -1, &top);
+        match transition.pop_symbol_id() {
+            Some(symbol) => {
+                let others = b.symbols_except(&stack[symbol].to_string());
+                b.rule(&top, &stack[symbol].to_string(), Some('_'), 0, &push_state(0));
+                b.rule(&top, &others, None, 0, "dead");
+            },
+            None => b.rule(&top, &all, None, 1, &push_state(0))
+        }
+
+        // Push the last symbol first so the first one ends up on top
+        for (i, symbol) in pushes.iter().rev().enumerate() {
+            b.rule(&push_state(i), "_", Some(stack[*symbol]), 1, &push_state(i + 1));
+        }
+    }
+
+    // Record that a sequence survived, or give up on it, then move on to the next sequence
+    let alive_home = home(&mut b, "set_alive");
+    b.rule("alive", &all, None, 0, &alive_home);
+    b.rule("set_alive", &string(&input), None, 1, "set_alive");
+    b.rule("set_alive", &string(&marked), None, 1, "set_alive");
+    b.rule("set_alive", &input_end.to_string(), None, 1, "set_alive");
+    b.rule("set_alive", &format!("{alive}{dead}"), Some(alive), 0, "dead");
+
+    let reset = home(&mut b, "reset");
+    b.rule("dead", &all, None, 0, &reset);
+    for (symbol, mark) in marked.iter().enumerate() {
+        b.rule("reset", &mark.to_string(), Some(input[symbol]), 1, "reset");
+    }
+    for (digit, mark) in spent.iter().enumerate() {
+        b.rule("reset", &mark.to_string(), Some(digits[digit]), 1, "reset");
+    }
+    b.rule("reset", &format!("{}{input_end}{alive}{dead}", string(&input)), None, 1, "reset");
+    b.rule("reset", &string(&digits), None, 1, "reset");
+    b.rule("reset", &stack_start.to_string(), None, 1, "erase");
+    b.rule("erase", &string(&stack), Some('_'), 1, "erase");
+    b.rule("erase", "_", None, -1, "erase_back");
+    b.rule("erase_back", "_", None, -1, "erase_back");
+    b.rule("erase_back", &stack_start.to_string(), None, -1, "increment");
+
+    // Count up in shortlex order, growing the sequence when every choice carries
+    for (choice, digit) in digits.iter().enumerate() {
+        match digits.get(choice + 1) {
+            Some(next) => b.rule("increment", &digit.to_string(), Some(*next), 0, &start_run),
+            None => b.rule("increment", &digit.to_string(), Some(digits[0]), -1, "increment")
+        }
+    }
+    b.rule("increment", &alive.to_string(), Some(dead), 1, "grow");
+    b.rule("increment", &dead.to_string(), None, 0, "reject");
+    b.rule("grow", &string(&digits), None, 1, "grow");
+    b.rule("grow", &stack_start.to_string(), Some(digits[0]), 1, "grow_end");
+    b.rule("grow_end", "_", Some(stack_start), 0, &start_run);
+
+    Ok(b.build())
+}
+
+/// A state in a strongly connected component of the epsilon transitions with a cycle that does not
+/// shrink the stack and a cycle that does not grow it, if any
+///
+/// A single cycle that keeps the stack height is both.
+fn neutral_epsilon_cycle(pda: &PushdownAutomaton) -> Option<usize> {
+    let states = pda.view_states().len();
+    let edges: Vec<(usize, usize, i64)> = pda.view_transitions().iter()
+        .filter(|transition| transition.read_symbol_id().is_none())
+        .map(|transition| {
+            let growth = transition.push_symbol_ids().len() as i64 - transition.pop_symbol_id().map_or(0, |_| 1);
+            (transition.from_state_id(), transition.next_state_id(), growth)
+        })
+        .collect();
+
+    // Two states share a component when each reaches the other
+    let mut reaches = vec![vec![false; states]; states];
+    for (state, reached) in reaches.iter_mut().enumerate() {
+        let mut pending = vec![state];
+        while let Some(from) = pending.pop() {
+            for (_, to, _) in edges.iter().filter(|(source, _, _)| *source == from) {
+                if !reached[*to] {
+                    reached[*to] = true;
+                    pending.push(*to);
+                }
+            }
+        }
+    }
+    // Each component is named by its lowest state
+    let component: Vec<usize> = (0..states)
+        .map(|state| (0..states).find(|other| *other == state || (reaches[state][*other] && reaches[*other][state])).unwrap())
+        .collect();
+    let mut roots = component.clone();
+    roots.sort();
+    roots.dedup();
+
+    roots.into_iter().find_map(|root| {
+        let inside: Vec<(usize, usize, i64)> = edges.iter().copied()
+            .filter(|(from, to, _)| component[*from] == root && component[*to] == root)
+            .collect();
+        let reversed: Vec<(usize, usize, i64)> = inside.iter().map(|(from, to, growth)| (*from, *to, -growth)).collect();
+
+        match (non_positive_cycle(states, &inside), non_positive_cycle(states, &reversed)) {
+            (Some(state), Some(_)) => Some(state),
+            _ => None
+        }
+    })
+}
+
+/// A state on a cycle whose weights sum to at most zero, if any
+///
+/// Weighting each edge by `(n + 1) * weight - 1` for `n` states, such a cycle is exactly a negative
+/// cycle, which Bellman-Ford finds.
+fn non_positive_cycle(states: usize, edges: &[(usize, usize, i64)]) -> Option<usize> {
+    let scale = states as i64 + 1;
+    let mut distance = vec![0; states];
+    for _ in 0..states {
+        for (from, to, weight) in edges {
+            distance[*to] = distance[*to].min(distance[*from] + scale * weight - 1);
+        }
+    }
+
+    edges.iter().find(|(from, to, weight)| distance[*from] + scale * weight - 1 < distance[*to]).map(|(from, _, _)| *from)
+}
@@ -0,0 +1,6 @@
+pub mod automaton;
+pub mod computation;
+pub mod convert;
+
+pub use crate::pushdown::automaton::PushdownAutomaton as PDA;
+pub use crate::pushdown::computation::Computation as PdaComputation;
@@ -0,0 +1,50 @@
+use crate::errors::TmError;
+use super::{ Tape, BLANK, one_way_target };
+
+/// A tape stored as a contiguous `Vec` of symbol ids
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DenseTape {
+    cells: Vec<usize>,
+    head: usize
+}
+
+impl DenseTape {
+    pub fn view_cells(&self) -> &Vec<usize> { &self.cells }
+}
+
+impl Tape for DenseTape {
+    fn load(symbols: &[usize], _alphabet_size: usize) -> Result<Self, TmError> {
+        Ok(DenseTape { cells: symbols.to_vec(), head: 0 })
+    }
+
+    fn get(&self, position: usize) -> usize {
+        self.cells.get(position).copied().unwrap_or(BLANK)
+    }
+
+    fn set(&mut self, position: usize, symbol: usize) {
+        self.cells[position] = symbol;
+    }
+
+    fn grow(&mut self) {
+        self.cells.push(BLANK);
+    }
+
+    fn len(&self) -> usize { self.cells.len() }
+    fn head(&self) -> usize { self.head }
+
+    fn move_head(&mut self, direction: i32) -> usize {
+        self.head = one_way_target(self.head, direction);
+
+        let length = self.cells.len();
+        if self.head >= length {
+            self.cells.resize(self.head + 1, BLANK);
+        }
+        self.cells.len() - length
+    }
+
+    fn to_vec(&self) -> Vec<usize> { self.cells.clone() }
+}
+
+impl PartialEq<Vec<usize>> for DenseTape {
+    fn eq(&self, other: &Vec<usize>) -> bool { &self.cells == other }
+}
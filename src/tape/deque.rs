@@ -0,0 +1,53 @@
+use std::collections::VecDeque;
+
+use crate::errors::TmError;
+use super::{ Tape, BLANK, one_way_target };
+
+/// A tape stored as a `VecDeque` of symbol ids
+///
+/// The head stops at the left end like every other one-way tape. See
+/// [`TwoWayTape`](super::TwoWayTape) for a tape that also grows to the left.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DequeTape {
+    cells: VecDeque<usize>,
+    head: usize
+}
+
+impl DequeTape {
+    pub fn view_cells(&self) -> &VecDeque<usize> { &self.cells }
+}
+
+impl Tape for DequeTape {
+    fn load(symbols: &[usize], _alphabet_size: usize) -> Result<Self, TmError> {
+        Ok(DequeTape { cells: symbols.iter().copied().collect(), head: 0 })
+    }
+
+    fn get(&self, position: usize) -> usize {
+        self.cells.get(position).copied().unwrap_or(BLANK)
+    }
+
+    fn set(&mut self, position: usize, symbol: usize) {
+        self.cells[position] = symbol;
+    }
+
+    fn grow(&mut self) {
+        self.cells.push_back(BLANK);
+    }
+
+    fn len(&self) -> usize { self.cells.len() }
+    fn head(&self) -> usize { self.head }
+
+    fn move_head(&mut self, direction: i32) -> usize {
+        self.head = one_way_target(self.head, direction);
+
+        let length = self.cells.len();
+        if self.head >= length {
+            self.cells.resize(self.head + 1, BLANK);
+        }
+        self.cells.len() - length
+    }
+}
+
+impl PartialEq<Vec<usize>> for DequeTape {
+    fn eq(&self, other: &Vec<usize>) -> bool { self.cells.iter().eq(other.iter()) }
+}
@@ -0,0 +1,117 @@
+//! # Tapes
+//!
+//! Storage backends for the tape of a computation.
+//!
+//! Every tape holds symbol ids (indices into the tape and language symbols of a machine, with `0` as
+//! the blank) together with the position of the head. Cells that were never written read as blank.
+//!
+//! * [`DenseTape`] - A `Vec` of symbol ids, the default and best all-rounder
+//! * [`DequeTape`] - A `VecDeque` of symbol ids
+//! * [`SparseTape`] - A `HashMap` of written cells, for machines whose head jumps widely
+//! * [`PackedTape`] - Symbols bit-packed into words, for small (e.g. binary) alphabets
+//!
+//! [`TwoWayTape`] also grows to the left, modelling a two-way infinite tape. It is the one tape that
+//! does not stop the head at the left end.
+
+pub mod dense;
+pub mod deque;
+pub mod sparse;
+pub mod packed;
+pub mod two_way;
+
+use crate::errors::TmError;
+
+pub use crate::tape::dense::DenseTape;
+pub use crate::tape::deque::DequeTape;
+pub use crate::tape::sparse::SparseTape;
+pub use crate::tape::packed::PackedTape;
+pub use crate::tape::two_way::TwoWayTape;
+
+/// The id of the blank symbol on every tape
+pub const BLANK: usize = 0;
+
+/// A tape of symbol ids with a single head
+pub trait Tape {
+    /// Load the initial contents of the tape with the head on the first cell
+    ///
+    /// `alphabet_size` is the number of distinct symbol ids that may ever be written.
+    fn load(symbols: &[usize], alphabet_size: usize) -> Result<Self, TmError> where Self: Sized;
+
+    /// Read the symbol at any cell, blank if outside the used extent
+    fn get(&self, position: usize) -> usize;
+
+    /// Write a symbol to any cell inside the used extent
+    fn set(&mut self, position: usize, symbol: usize);
+
+    /// Extend the used extent by a single blank cell on the right
+    fn grow(&mut self);
+
+    /// The number of cells in the used extent
+    fn len(&self) -> usize;
+
+    /// The current position of the head
+    fn head(&self) -> usize;
+
+    /// Move the head by `direction` cells and return the number of cells the tape grew by
+    ///
+    /// The head stops at the left end of the tape and the tape grows to the right to keep the head
+    /// inside the used extent. A [`TwoWayTape`] instead grows to the left as well.
+    fn move_head(&mut self, direction: i32) -> usize;
+
+    fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Read the symbol under the head
+    fn read(&self) -> usize { self.get(self.head()) }
+
+    /// Write a symbol under the head
+    fn write(&mut self, symbol: usize) { self.set(self.head(), symbol) }
+
+    /// Iterate over the symbols in the cells `start..end`
+    fn window(&self, start: usize, end: usize) -> Window<'_, Self> where Self: Sized {
+        Window { tape: self, position: start, end }
+    }
+
+    /// Copy the used extent of the tape into a `Vec`
+    fn to_vec(&self) -> Vec<usize> where Self: Sized {
+        self.window(0, self.len()).collect()
+    }
+}
+
+/// Iterator over a range of cells of a tape
+pub struct Window<'a, T: Tape> {
+    tape: &'a T,
+    position: usize,
+    end: usize
+}
+
+impl<T: Tape> Iterator for Window<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.position >= self.end {
+            return None;
+        }
+
+        self.position += 1;
+        Some(self.tape.get(self.position - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end.saturating_sub(self.position);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: Tape> ExactSizeIterator for Window<'_, T> {}
+
+/// Head position after a move on a tape that is bounded on the left
+pub(crate) fn one_way_target(head: usize, direction: i32) -> usize {
+    if direction.is_negative() {
+        head.saturating_sub(direction.unsigned_abs() as usize)
+    } else {
+        match head.checked_add(direction as usize) {
+            Some(val) => val,
+            None => panic!("Head position exceded max usize")
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::errors::TmError;
+use super::{ Tape, BLANK, one_way_target };
+
+/// A tape with symbol ids bit-packed into 64 bit words
+///
+/// Each cell takes just enough bits for the alphabet, so a machine over a binary language (blank,
+/// start marker, `0` and `1`) stores 32 cells per word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedTape {
+    words: Vec<u64>,
+    bits: usize,
+    length: usize,
+    head: usize
+}
+
+impl PackedTape {
+    fn cells_per_word(&self) -> usize { 64 / self.bits }
+    fn mask(&self) -> u64 { (1 << self.bits) - 1 }
+
+    /// The number of bits used to store each cell
+    pub fn view_bits(&self) -> usize { self.bits }
+
+    fn reserve_cells(&mut self, length: usize) {
+        let words = length.div_ceil(self.cells_per_word());
+        if words > self.words.len() {
+            self.words.resize(words, 0);
+        }
+        self.length = self.length.max(length);
+    }
+}
+
+impl Tape for PackedTape {
+    fn load(symbols: &[usize], alphabet_size: usize) -> Result<Self, TmError> {
+        let bits = match alphabet_size {
+            0..=2 => 1,
+            size => (usize::BITS - (size - 1).leading_zeros()) as usize
+        };
+
+        if bits > 32 {
+            return Err(TmError::new(
+                format!("Alphabet of {} symbols is too large for a packed tape.", alphabet_size)
+            ));
+        }
+
+        let mut tape = PackedTape { words: vec![], bits, length: 0, head: 0 };
+        tape.reserve_cells(symbols.len());
+
+        for (i, symbol) in symbols.iter().enumerate() {
+            if *symbol >= 1 << bits {
+                return Err(TmError::new(
+                    format!("Symbol id {} at position {} does not fit in {} bits.", symbol, i, bits)
+                ));
+            }
+            tape.set(i, *symbol);
+        }
+
+        Ok(tape)
+    }
+
+    fn get(&self, position: usize) -> usize {
+        if position >= self.length {
+            return BLANK;
+        }
+
+        let per_word = self.cells_per_word();
+        let shift = (position % per_word) * self.bits;
+        ((self.words[position / per_word] >> shift) & self.mask()) as usize
+    }
+
+    fn set(&mut self, position: usize, symbol: usize) {
+        assert!(position < self.length, "Cell {} is outside the tape", position);
+        assert!(symbol < 1 << self.bits, "Symbol id {} does not fit in {} bits", symbol, self.bits);
+
+        let per_word = self.cells_per_word();
+        let shift = (position % per_word) * self.bits;
+        let mask = self.mask();
+        let word = &mut self.words[position / per_word];
+        *word = (*word & !(mask << shift)) | ((symbol as u64 & mask) << shift);
+    }
+
+    fn grow(&mut self) {
+        self.reserve_cells(self.length + 1);
+    }
+
+    fn len(&self) -> usize { self.length }
+    fn head(&self) -> usize { self.head }
+
+    fn move_head(&mut self, direction: i32) -> usize {
+        self.head = one_way_target(self.head, direction);
+
+        let length = self.length;
+        self.reserve_cells(self.head + 1);
+        self.length - length
+    }
+}
+
+impl PartialEq<Vec<usize>> for PackedTape {
+    fn eq(&self, other: &Vec<usize>) -> bool { self.window(0, self.length).eq(other.iter().copied()) }
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use crate::errors::TmError;
+use super::{ Tape, BLANK, one_way_target };
+
+/// A tape that only stores the cells holding a non-blank symbol
+///
+/// Growing the tape is free, which suits machines that move the head far across untouched cells.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SparseTape {
+    cells: HashMap<usize, usize>,
+    length: usize,
+    head: usize
+}
+
+impl Tape for SparseTape {
+    fn load(symbols: &[usize], _alphabet_size: usize) -> Result<Self, TmError> {
+        let cells = symbols.iter().copied()
+            .enumerate()
+            .filter(|(_, symbol)| *symbol != BLANK)
+            .collect();
+
+        Ok(SparseTape { cells, length: symbols.len(), head: 0 })
+    }
+
+    fn get(&self, position: usize) -> usize {
+        self.cells.get(&position).copied().unwrap_or(BLANK)
+    }
+
+    fn set(&mut self, position: usize, symbol: usize) {
+        if symbol == BLANK {
+            self.cells.remove(&position);
+        } else {
+            self.cells.insert(position, symbol);
+        }
+    }
+
+    fn grow(&mut self) {
+        self.length += 1;
+    }
+
+    fn len(&self) -> usize { self.length }
+    fn head(&self) -> usize { self.head }
+
+    fn move_head(&mut self, direction: i32) -> usize {
+        self.head = one_way_target(self.head, direction);
+
+        let length = self.length;
+        self.length = self.length.max(self.head + 1);
+        self.length - length
+    }
+}
+
+impl PartialEq<Vec<usize>> for SparseTape {
+    fn eq(&self, other: &Vec<usize>) -> bool { self.window(0, self.length).eq(other.iter().copied()) }
+}
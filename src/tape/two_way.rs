@@ -0,0 +1,68 @@
+use std::collections::VecDeque;
+
+use crate::errors::TmError;
+use super::{ Tape, BLANK };
+
+/// A two-way infinite tape stored as a `VecDeque` of symbol ids
+///
+/// Unlike the other tapes the head does not stop at the left end: moving past it prepends blank
+/// cells, so positions are always relative to the leftmost cell that has been visited. Machines
+/// written for a one-way tape can behave differently on it, so it is only meant for machines written
+/// for a two-way tape, such as the [busy beavers](crate::deterministic::catalogue::busy_beaver).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TwoWayTape {
+    cells: VecDeque<usize>,
+    head: usize
+}
+
+impl TwoWayTape {
+    pub fn view_cells(&self) -> &VecDeque<usize> { &self.cells }
+}
+
+impl Tape for TwoWayTape {
+    fn load(symbols: &[usize], _alphabet_size: usize) -> Result<Self, TmError> {
+        Ok(TwoWayTape { cells: symbols.iter().copied().collect(), head: 0 })
+    }
+
+    fn get(&self, position: usize) -> usize {
+        self.cells.get(position).copied().unwrap_or(BLANK)
+    }
+
+    fn set(&mut self, position: usize, symbol: usize) {
+        self.cells[position] = symbol;
+    }
+
+    fn grow(&mut self) {
+        self.cells.push_back(BLANK);
+    }
+
+    fn len(&self) -> usize { self.cells.len() }
+    fn head(&self) -> usize { self.head }
+
+    fn move_head(&mut self, direction: i32) -> usize {
+        let length = self.cells.len();
+        let distance = direction.unsigned_abs() as usize;
+
+        if direction.is_negative() {
+            if distance > self.head {
+                for _ in 0..(distance - self.head) {
+                    self.cells.push_front(BLANK);
+                }
+                self.head = 0;
+            } else {
+                self.head -= distance;
+            }
+        } else {
+            self.head += distance;
+            if self.head >= length {
+                self.cells.resize(self.head + 1, BLANK);
+            }
+        }
+
+        self.cells.len() - length
+    }
+}
+
+impl PartialEq<Vec<usize>> for TwoWayTape {
+    fn eq(&self, other: &Vec<usize>) -> bool { self.cells.iter().eq(other.iter()) }
+}
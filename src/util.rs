@@ -17,7 +17,7 @@ impl State {
     }
     
     pub fn as_str(&self) -> &str {
-        &self.0.as_str()
+        self.0.as_str()
     }
 }
 
@@ -85,7 +85,7 @@ impl fmt::Display for Transition {
     pub max_space: Option<usize>,
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum ComputationStatus {
     #[default]
     Executing,
@@ -108,4 +108,24 @@ impl ComputationClock {
             max_time, max_space, time: 0, space: tape_length
         }
     }
+
+    /// Count one step, returning [`ComputationStatus::Timeout`] once the time limit is reached
+    pub fn tick(&mut self) -> Option<ComputationStatus> {
+        self.time += 1;
+        match self.max_time {
+            Some(limit) if self.time >= limit => Some(ComputationStatus::Timeout),
+            _ => None
+        }
+    }
+
+    /// Record the space in use, returning [`ComputationStatus::Spaceout`] once the space limit is reached
+    pub fn occupy(&mut self, space: usize) -> Option<ComputationStatus> {
+        self.space = space;
+        if self.fits(space) { None } else { Some(ComputationStatus::Spaceout) }
+    }
+
+    /// Whether `space` stays below the space limit
+    pub fn fits(&self, space: usize) -> bool {
+        self.max_space.is_none_or(|limit| space < limit)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+use std::process::{ Command, Output };
+
+use tm_engine::deterministic::{ catalogue, format, universal };
+
+fn tm(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tm")).args(args).output().unwrap()
+}
+
+/// Write a file into the scratch directory of the integration tests
+fn scratch(name: &str, text: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, text).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn run_exit_codes() {
+    let path = scratch("palindromes.tm", &format::to_text(&catalogue::palindromes()).unwrap());
+
+    let accepted = tm(&["run", &path, "0110"]);
+    assert_eq!(accepted.status.code(), Some(0));
+    assert!(stdout(&accepted).contains("status Accept"));
+
+    let rejected = tm(&["run", &path, "0111"]);
+    assert_eq!(rejected.status.code(), Some(1));
+    assert!(stdout(&rejected).contains("status Reject"));
+
+    assert_eq!(tm(&["run", &path, "0110", "--time", "3"]).status.code(), Some(3));
+    assert_eq!(tm(&["run", &path, "0110", "--space", "3"]).status.code(), Some(4));
+
+    let traced = tm(&["trace", &path, "0", "--window", "4"]);
+    assert_eq!(traced.status.code(), Some(0));
+    assert!(stdout(&traced).lines().count() > 5);
+}
+
+#[test]
+fn check_and_convert() {
+    let valid = scratch("check.tm", &format::to_text(&catalogue::palindromes()).unwrap());
+    let checked = tm(&["check", &valid]);
+    assert_eq!(checked.status.code(), Some(0));
+    assert!(stdout(&checked).starts_with("valid "));
+
+    let invalid = scratch("invalid.tm", "tape _ >\nlanguage 0\n");
+    let checked = tm(&["check", &invalid]);
+    assert_eq!(checked.status.code(), Some(1));
+    assert!(stdout(&checked).starts_with("invalid "));
+
+    let encoding = universal::encode(&catalogue::palindromes(), "").unwrap();
+    let encoded = scratch("check.utm", &encoding);
+    let checked = tm(&["check", &encoded]);
+    assert_eq!(checked.status.code(), Some(0));
+    assert!(stdout(&checked).starts_with("valid "));
+
+    // An accept state far past the last state
+    let (header, rest) = encoding.split_once('#').unwrap();
+    let (header, reject) = header.rsplit_once(',').unwrap();
+    let (header, _) = header.rsplit_once(',').unwrap();
+    let invalid = scratch("invalid.utm", &format!("{},1111111,{}#{}", header, reject, rest));
+    let checked = tm(&["check", &invalid]);
+    assert_eq!(checked.status.code(), Some(1));
+    assert!(stdout(&checked).starts_with("invalid "));
+    assert_eq!(tm(&["run", &invalid, "0"]).status.code(), Some(2));
+
+    let dot = tm(&["convert", &valid, "dot"]);
+    assert_eq!(dot.status.code(), Some(0));
+    assert!(stdout(&dot).starts_with("digraph {"));
+
+    let round_trip = tm(&["convert", &valid, "tm"]);
+    assert_eq!(stdout(&round_trip), format::to_text(&catalogue::palindromes()).unwrap());
+}
+
+#[test]
+fn usage_errors_and_unreadable_machines() {
+    let missing = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("missing.tm");
+    let missing = missing.to_string_lossy();
+    let missing_encoding = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("missing.utm");
+    let missing_encoding = missing_encoding.to_string_lossy();
+
+    for args in [vec![], vec!["walk"], vec!["run", "machine.tm"], vec!["check", &missing], vec!["run", &missing, "0"], vec!["check", &missing_encoding]] {
+        let output = tm(&args);
+        assert_eq!(output.status.code(), Some(2), "for {:?}", args);
+        assert!(output.stdout.is_empty(), "for {:?}", args);
+        assert!(!output.stderr.is_empty(), "for {:?}", args);
+    }
+
+    let valid = scratch("flags.tm", &format::to_text(&catalogue::palindromes()).unwrap());
+    assert_eq!(tm(&["run", &valid, "0", "--time", "soon"]).status.code(), Some(2));
+    assert_eq!(tm(&["run", &valid, "0", "--fast"]).status.code(), Some(2));
+    assert_eq!(tm(&["convert", &valid, "pdf"]).status.code(), Some(2));
+}